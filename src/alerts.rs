@@ -0,0 +1,71 @@
+use crate::monitor::ServiceStatus;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+/// A service moving from one status to another between checks.
+#[derive(Debug, Clone, Serialize)]
+pub struct Transition {
+    pub host: String,
+    pub service: String,
+    pub old_status: ServiceStatus,
+    pub new_status: ServiceStatus,
+    pub timestamp: DateTime<Utc>,
+    pub error: Option<String>,
+}
+
+impl Transition {
+    pub fn summary(&self) -> String {
+        format!("{} / {}: {} -> {}", self.host, self.service, self.old_status, self.new_status)
+    }
+}
+
+pub async fn send_desktop_notification(transition: Transition) {
+    let result = tokio::task::spawn_blocking(move || {
+        let mut body = format!("{} -> {}", transition.old_status, transition.new_status);
+        if let Some(error) = &transition.error {
+            body.push('\n');
+            body.push_str(error);
+        }
+
+        notify_rust::Notification::new()
+            .summary(&format!("{} / {}", transition.host, transition.service))
+            .body(&body)
+            .appname("daystrom-tui")
+            .show()
+            .map(|_| ())
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => warn!("Failed to show desktop notification: {}", e),
+        Err(e) => warn!("Desktop notification task failed: {}", e),
+    }
+}
+
+/// POSTs the transition as JSON to `url`, retrying once before giving up.
+pub async fn send_webhook(client: &Client, url: &str, transition: &Transition) {
+    for attempt in 1..=2 {
+        let result = client
+            .post(url)
+            .json(transition)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        match result {
+            Ok(_) => {
+                debug!("Delivered webhook for {}", transition.summary());
+                return;
+            }
+            Err(e) if attempt == 1 => {
+                debug!("Webhook delivery failed, retrying: {}", e);
+            }
+            Err(e) => {
+                warn!("Failed to deliver webhook for {}: {}", transition.summary(), e);
+            }
+        }
+    }
+}
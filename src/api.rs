@@ -0,0 +1,36 @@
+use crate::monitor::MonitorEngine;
+use crate::server::{self, Response};
+use anyhow::Result;
+
+const JSON: &str = "application/json";
+
+/// Serves a read-only JSON API: `/status` returns every current result and
+/// `/healthz` answers as long as the monitor is running.
+pub async fn serve(port: u16, engine: MonitorEngine) -> Result<tokio::task::JoinHandle<()>> {
+    server::serve("status API", port, move |path| {
+        let engine = engine.clone();
+        async move {
+            match path.as_str() {
+                "/status" => {
+                    let statuses = engine.get_sorted_statuses().await;
+                    match serde_json::to_string_pretty(&statuses) {
+                        Ok(body) => Response::ok(JSON, body),
+                        Err(e) => Response::internal_error(format!("Failed to serialize statuses: {}", e)),
+                    }
+                }
+                "/healthz" => Response::ok(JSON, healthz(&engine)),
+                _ => Response::not_found(),
+            }
+        }
+    })
+    .await
+}
+
+fn healthz(engine: &MonitorEngine) -> String {
+    serde_json::json!({
+        "status": "ok",
+        "paused": engine.is_paused(),
+        "last_result_at": engine.last_result_at().to_rfc3339(),
+    })
+    .to_string()
+}
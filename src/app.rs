@@ -1,52 +1,367 @@
+use crate::alerts::Transition;
 use crate::config::Config;
-use crate::monitor::{MonitorEngine, ServiceCheck};
+use crate::export;
+use crate::monitor::{service_url, CheckSample, CheckTiming, MonitorEngine, ServiceCheck, ServiceStatus, StatusSnapshot};
+use crate::peers::{self, PeerSnapshot};
+use crate::theme::{NumberFormat, RowHighlight, StatusStyle, Theme};
+use anyhow::{Context, Result};
 use chrono::Utc;
-use std::collections::HashMap;
-use std::time::Duration;
+use ratatui::widgets::TableState;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+// Results are considered stale after this many missed refresh intervals
+const STALE_INTERVALS: u64 = 3;
+
+// How long a transient status-bar message stays visible
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+
+// Fewer samples than this make tail percentiles meaningless
+const MIN_PERCENTILE_SAMPLES: usize = 5;
+
+// Services plotted when no single service is selected
+const GRAPH_TOP_SERVICES: usize = 5;
+
+// Share of its interval the slowest check may take before it is flagged
+const FALLING_BEHIND_RATIO: f64 = 0.8;
+
+/// Response-time percentiles over a service's recent answered checks.
+#[derive(Debug, Clone, Copy)]
+pub struct ResponsePercentiles {
+    pub samples: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    Name,
+    Status,
+    ResponseTime,
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Name => SortMode::Status,
+            SortMode::Status => SortMode::ResponseTime,
+            SortMode::ResponseTime => SortMode::Name,
+        }
+    }
+
+    fn compare(self, a: &ServiceCheck, b: &ServiceCheck) -> Ordering {
+        match self {
+            SortMode::Name => Ordering::Equal,
+            SortMode::Status => b.status.severity().cmp(&a.status.severity()),
+            SortMode::ResponseTime => b.response_time.cmp(&a.response_time),
+        }
+    }
+}
+
+/// Layout of the services table, from `settings.view_mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewMode {
+    /// Services under a header row per host.
+    Grouped,
+    /// One row per service with the host in its own column.
+    Flat,
+}
+
+impl ViewMode {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "grouped" => ViewMode::Grouped,
+            "flat" => ViewMode::Flat,
+            other => {
+                warn!("Unknown view mode '{}', falling back to grouped", other);
+                ViewMode::Grouped
+            }
+        }
+    }
+}
+
+/// Order of hosts when the sort mode doesn't decide it, from `settings.host_order`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostOrder {
+    Alpha,
+    Config,
+    Status,
+}
+
+impl HostOrder {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "alpha" => HostOrder::Alpha,
+            "config" => HostOrder::Config,
+            "status" => HostOrder::Status,
+            other => {
+                warn!("Unknown host order '{}', falling back to alpha", other);
+                HostOrder::Alpha
+            }
+        }
+    }
+}
+
+/// How per-service uptime combines into fleet availability, from
+/// `settings.availability_weighting`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AvailabilityWeighting {
+    /// Average of each service's availability.
+    Service,
+    /// Share of all recent checks that were up, so frequently checked
+    /// services weigh more.
+    Checks,
+}
+
+impl AvailabilityWeighting {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "service" => AvailabilityWeighting::Service,
+            "checks" => AvailabilityWeighting::Checks,
+            other => {
+                warn!("Unknown availability weighting '{}', falling back to service", other);
+                AvailabilityWeighting::Service
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SortMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortMode::Name => write!(f, "name"),
+            SortMode::Status => write!(f, "status"),
+            SortMode::ResponseTime => write!(f, "response time"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SummaryStats {
+    pub up: usize,
+    pub degraded: usize,
+    pub down: usize,
+    pub unknown: usize,
+    /// Services whose first check hasn't finished yet.
+    pub pending: usize,
+    /// Services in a maintenance window; never counted as down.
+    pub maintenance: usize,
+    /// Failing services someone has acknowledged; not counted in the other buckets.
+    pub acknowledged: usize,
+}
+
+/// Hosts by how many of their services are failing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HostSummary {
+    pub healthy: usize,
+    /// Some, but not all, services down or otherwise failing.
+    pub degraded: usize,
+    /// Every service down.
+    pub down: usize,
+}
 
 #[derive(Debug, Clone)]
 pub enum SelectedItem {
     HostHeader(String),
-    Service(ServiceCheck),
+    Service(Box<ServiceCheck>),
 }
 
 #[derive(Debug)]
 pub struct App {
     pub config: Config,
     pub monitor_engine: MonitorEngine,
-    pub statuses: HashMap<String, ServiceCheck>,
+    pub statuses: StatusSnapshot,
+    pub samples: HashMap<String, VecDeque<CheckSample>>,
+    /// Latest results pulled from `settings.peers`.
+    pub peer_results: PeerSnapshot,
+    /// This instance's own results, before peers' are rolled into `statuses`.
+    pub local_statuses: StatusSnapshot,
     pub selected_index: usize,
     pub show_help: bool,
+    pub help_scroll: u16,
     pub show_host_detail: bool,
     pub selected_host_name: Option<String>,
+    pub show_service_detail: bool,
+    pub selected_service_key: Option<String>,
+    /// Scroll offset of the error panel in the detail views.
+    pub detail_scroll: u16,
+    /// Selected row of the host detail services table.
+    pub detail_selected_index: usize,
+    /// Flat list of down and degraded services in place of the host groups.
+    pub show_triage: bool,
+    pub triage_selected_index: usize,
+    /// Full-screen response time graph of `graph_keys`.
+    pub show_graph: bool,
+    pub graph_keys: Vec<String>,
+    /// Full-screen log of status changes, newest first.
+    pub show_events: bool,
+    pub events_scroll: u16,
+    /// Status changes seen this run, oldest first; bounded by the engine.
+    pub events: VecDeque<Transition>,
+    pub check_timing: CheckTiming,
     pub last_update: chrono::DateTime<Utc>,
+    pub filter: String,
+    pub filter_editing: bool,
+    /// Only services with this tag are shown and counted.
+    pub tag_filter: Option<String>,
+    pub sort_mode: SortMode,
+    pub host_order: HostOrder,
+    pub view_mode: ViewMode,
+    pub availability_weighting: AvailabilityWeighting,
+    pub paused: bool,
+    pub table_state: TableState,
+    pub theme: Theme,
+    pub status_style: StatusStyle,
+    pub row_highlight: RowHighlight,
+    pub number_format: NumberFormat,
+    pub collapsed_hosts: HashSet<String>,
+    pub acknowledged: HashSet<String>,
+    /// Free-text notes on services, keyed by status key; kept whatever the
+    /// status does, and saved to `notes_file` when set.
+    pub notes: HashMap<String, String>,
+    /// Service whose note is being typed, if any.
+    pub note_target: Option<String>,
+    pub note_input: String,
+    status_message: Option<(String, Instant)>,
+    /// Down count at the last update; unset until every service has reported
+    /// once, so the first results coming in don't ring the bell.
+    last_down_count: Option<usize>,
+    bell_pending: bool,
+    statuses_version: Option<u64>,
+    manual_check: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl App {
     pub fn new(config: Config, monitor_engine: MonitorEngine) -> Self {
+        let theme = Theme::from_name(&config.settings.theme);
+        let status_style = StatusStyle::from_name(&config.settings.status_style);
+        let row_highlight = RowHighlight::from_name(&config.settings.row_highlight);
+        let host_order = HostOrder::from_name(&config.settings.host_order);
+        let view_mode = ViewMode::from_name(&config.settings.view_mode);
+        let availability_weighting = AvailabilityWeighting::from_name(&config.settings.availability_weighting);
+        let number_format = NumberFormat {
+            decimals: config.settings.response_time_decimals,
+            thousands_separator: config.settings.thousands_separator.clone(),
+        };
+        let notes = match config.settings.notes_file.as_deref().map(Path::new).filter(|path| path.exists()) {
+            Some(path) => load_notes(path).unwrap_or_else(|e| {
+                warn!("Ignoring notes file: {:#}", e);
+                HashMap::new()
+            }),
+            None => HashMap::new(),
+        };
         Self {
             config,
             monitor_engine,
-            statuses: HashMap::new(),
+            statuses: StatusSnapshot::default(),
+            samples: HashMap::new(),
+            peer_results: PeerSnapshot::default(),
+            local_statuses: StatusSnapshot::default(),
             selected_index: 0,
             show_help: false,
+            help_scroll: 0,
             show_host_detail: false,
             selected_host_name: None,
+            show_service_detail: false,
+            selected_service_key: None,
+            detail_scroll: 0,
+            detail_selected_index: 0,
+            show_triage: false,
+            triage_selected_index: 0,
+            show_graph: false,
+            graph_keys: Vec::new(),
+            show_events: false,
+            events_scroll: 0,
+            events: VecDeque::new(),
+            check_timing: CheckTiming::default(),
             last_update: Utc::now(),
+            filter: String::new(),
+            filter_editing: false,
+            tag_filter: None,
+            sort_mode: SortMode::Name,
+            host_order,
+            view_mode,
+            availability_weighting,
+            paused: false,
+            table_state: TableState::default(),
+            theme,
+            status_style,
+            row_highlight,
+            number_format,
+            collapsed_hosts: HashSet::new(),
+            acknowledged: HashSet::new(),
+            notes,
+            note_target: None,
+            note_input: String::new(),
+            status_message: None,
+            last_down_count: None,
+            bell_pending: false,
+            statuses_version: None,
+            manual_check: None,
         }
     }
 
     pub async fn update_statuses(&mut self) {
-        self.statuses = self.monitor_engine.get_statuses().await;
+        self.statuses_version = Some(self.monitor_engine.version());
+        self.peer_results = self.monitor_engine.get_peer_results().await;
+        self.local_statuses = self.monitor_engine.get_statuses().await;
+        self.statuses = roll_up_peers(self.local_statuses.clone(), &self.peer_results);
+        self.samples = self.monitor_engine.get_samples().await;
+        self.events = self.monitor_engine.recent_transitions();
+        self.check_timing = self.monitor_engine.check_timing();
         self.last_update = Utc::now();
+        
+        // Acknowledgements only last until the service recovers
+        let statuses = &self.statuses;
+        self.acknowledged.retain(|key| {
+            statuses
+                .get(key)
+                .is_some_and(|status| status.status != ServiceStatus::Up)
+        });
+        self.sync_table_selection();
+        self.check_new_outages();
+    }
+
+    /// Queues a bell when the down count has grown since the last update.
+    fn check_new_outages(&mut self) {
+        // Wait until every service has a first result to compare against
+        if self.statuses.values().any(|s| s.status == ServiceStatus::Pending && !s.disabled) {
+            return;
+        }
+        
+        let down = self.get_summary_stats().down;
+        if self.config.settings.audible_alerts && self.last_down_count.is_some_and(|last| down > last) {
+            self.bell_pending = true;
+        }
+        self.last_down_count = Some(down);
+    }
+
+    /// Tracks the down count from the current view, so changing the tag
+    /// filter or acknowledgements doesn't look like a new outage.
+    fn rebaseline_down_count(&mut self) {
+        if self.last_down_count.is_some() {
+            self.last_down_count = Some(self.get_summary_stats().down);
+        }
+    }
+
+    /// Whether the terminal bell should ring; clears the request.
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell_pending)
     }
 
     pub fn get_status_list(&self) -> Vec<ServiceCheck> {
         let mut statuses: Vec<_> = self.statuses.values().cloned().collect();
         statuses.sort_by(|a, b| {
-            a.host_name
-                .cmp(&b.host_name)
-                .then(a.service_name.cmp(&b.service_name))
+            self.sort_mode
+                .compare(a, b)
+                .then_with(|| a.host_name.cmp(&b.host_name))
+                .then_with(|| a.service_name.cmp(&b.service_name))
+                .then_with(|| a.key.cmp(&b.key))
         });
         statuses
     }
@@ -57,13 +372,42 @@ impl App {
     }
 
     pub fn next_item(&mut self) {
+        if self.in_host_detail_table() {
+            let total = self.detail_services().len();
+            if total > 0 {
+                self.detail_selected_index = (self.detail_selected_index + 1) % total;
+            }
+            return;
+        }
+        if self.in_triage_list() {
+            let total = self.triage_services().len();
+            if total > 0 {
+                self.triage_selected_index = (self.triage_selected_index + 1) % total;
+            }
+            return;
+        }
         let total_items = self.get_total_items();
         if total_items > 0 {
             self.selected_index = (self.selected_index + 1) % total_items;
         }
+        self.sync_table_selection();
     }
 
     pub fn previous_item(&mut self) {
+        if self.in_host_detail_table() {
+            let total = self.detail_services().len();
+            if total > 0 {
+                self.detail_selected_index = (self.detail_selected_index + total - 1) % total;
+            }
+            return;
+        }
+        if self.in_triage_list() {
+            let total = self.triage_services().len();
+            if total > 0 {
+                self.triage_selected_index = (self.triage_selected_index + total - 1) % total;
+            }
+            return;
+        }
         let total_items = self.get_total_items();
         if total_items > 0 {
             self.selected_index = if self.selected_index == 0 {
@@ -72,30 +416,536 @@ impl App {
                 self.selected_index - 1
             };
         }
+        self.sync_table_selection();
+    }
+
+    /// Moves to the next host whose name starts with `letter`, wrapping around
+    /// so repeated presses cycle through every match.
+    pub fn jump_to_host(&mut self, letter: char) {
+        let rows = self.visible_rows();
+        let total = rows.len();
+        if total == 0 {
+            return;
+        }
+        
+        let letter = letter.to_lowercase().to_string();
+        let found = (1..=total)
+            .map(|offset| (self.selected_index + offset) % total)
+            .find(|&index| match &rows[index] {
+                SelectedItem::HostHeader(name) => name.to_lowercase().starts_with(&letter),
+                // In the flat view, the first of a run of the host's services
+                SelectedItem::Service(service) => {
+                    self.view_mode == ViewMode::Flat
+                        && service.host_name.to_lowercase().starts_with(&letter)
+                        && (index == 0
+                            || !matches!(&rows[index - 1], SelectedItem::Service(previous) if previous.host_name == service.host_name))
+                }
+            });
+        
+        if let Some(index) = found {
+            self.selected_index = index;
+            self.sync_table_selection();
+        }
+    }
+
+    /// Row in the services table holding the selection.
+    pub fn selected_row(&self) -> Option<usize> {
+        (self.selected_index < self.get_total_items()).then_some(self.selected_index)
+    }
+
+    /// Points the table state at the selected host so rendering scrolls it into view.
+    pub fn sync_table_selection(&mut self) {
+        let row = self.selected_row();
+        self.table_state.select(row);
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.monitor_engine.set_paused(self.paused);
+    }
+
+    /// Pulls the latest statuses. While paused the engine isn't checking on its
+    /// own, so this kicks off a one-off check cycle in the background instead.
+    pub async fn manual_refresh(&mut self) {
+        if self.paused {
+            if self.manual_check.is_none() {
+                let engine = self.monitor_engine.clone();
+                self.manual_check = Some(tokio::spawn(async move {
+                    engine.check_all_services().await;
+                }));
+            }
+        } else {
+            self.update_statuses().await;
+        }
+    }
+
+    /// Re-checks just the selected host or service in the background, and
+    /// notes the target in the status bar.
+    pub fn refresh_selected(&mut self) {
+        let selected = if self.show_service_detail {
+            self.get_detail_service().cloned().map(|service| SelectedItem::Service(Box::new(service)))
+        } else if self.show_host_detail {
+            self.selected_detail_service().map(|service| SelectedItem::Service(Box::new(service)))
+        } else if self.show_triage {
+            self.selected_triage_service().map(|service| SelectedItem::Service(Box::new(service)))
+        } else {
+            self.get_selected_item()
+        };
+        
+        let engine = self.monitor_engine.clone();
+        let (target, check) = match selected {
+            Some(SelectedItem::Service(service)) => {
+                let Some((host_index, service_index)) = service.config_indices() else {
+                    return;
+                };
+                (
+                    format!("{}/{}", service.host_name, service.service_name),
+                    tokio::spawn(async move { engine.check_one(host_index, service_index).await }),
+                )
+            }
+            Some(SelectedItem::HostHeader(host_name)) => {
+                let Some(host_index) = self.config.hosts.iter().position(|host| host.name == host_name) else {
+                    return;
+                };
+                (host_name, tokio::spawn(async move { engine.check_host(host_index).await }))
+            }
+            None => return,
+        };
+        
+        // While paused, tick() only picks up results once this finishes
+        self.manual_check = Some(check);
+        self.set_status_message(format!("Refreshing {}", target));
+    }
+
+    /// Called on every UI tick. Returns whether the statuses changed and the
+    /// screen needs redrawing.
+    pub async fn tick(&mut self) -> bool {
+        if !self.paused {
+            if self.statuses_version == Some(self.monitor_engine.version()) {
+                return false;
+            }
+            self.update_statuses().await;
+            return true;
+        }
+
+        if self.manual_check.as_ref().is_some_and(|check| check.is_finished()) {
+            self.manual_check = None;
+            self.update_statuses().await;
+            return true;
+        }
+
+        false
+    }
+
+    pub fn cycle_sort_mode(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+        self.sync_table_selection();
     }
 
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
+        self.help_scroll = 0;
     }
 
-    pub fn enter_host_detail(&mut self) {
-        if let Some(selected_item) = self.get_selected_item() {
-            match selected_item {
-                SelectedItem::HostHeader(host_name) => {
-                    self.selected_host_name = Some(host_name);
-                    self.show_host_detail = true;
-                }
-                SelectedItem::Service(_) => {
-                    // Services are no longer selectable, so this shouldn't happen
-                    // But we'll keep it for safety
-                }
+    pub fn scroll_help(&mut self, lines: i32) {
+        self.help_scroll = self.help_scroll.saturating_add_signed(lines as i16);
+    }
+
+    /// Opens the detail view for the selection: host detail on a host header,
+    /// service detail on a service row. From the host detail view this drills
+    /// into the selected service; `b` then returns to the host.
+    pub fn enter_detail(&mut self) {
+        self.detail_scroll = 0;
+        if self.in_host_detail_table() {
+            if let Some(service) = self.selected_detail_service() {
+                self.selected_service_key = Some(service.key);
+                self.show_service_detail = true;
+            }
+            return;
+        }
+        if self.in_triage_list() {
+            if let Some(service) = self.selected_triage_service() {
+                self.selected_service_key = Some(service.key);
+                self.show_service_detail = true;
+            }
+            return;
+        }
+        self.detail_selected_index = 0;
+        match self.get_selected_item() {
+            Some(SelectedItem::HostHeader(host_name)) => {
+                self.selected_host_name = Some(host_name);
+                self.show_host_detail = true;
+            }
+            Some(SelectedItem::Service(service)) => {
+                self.selected_service_key = Some(service.key);
+                self.show_service_detail = true;
+            }
+            None => {}
+        }
+    }
+
+    pub fn in_detail_view(&self) -> bool {
+        self.show_host_detail || self.show_service_detail
+    }
+
+    /// Whether the full-screen graph or status change log covers the views.
+    pub fn in_overlay(&self) -> bool {
+        self.show_graph || self.show_events
+    }
+
+    /// Whether the host detail services table is showing and takes navigation.
+    pub fn in_host_detail_table(&self) -> bool {
+        self.show_host_detail && !self.show_service_detail
+    }
+
+    fn detail_services(&self) -> Vec<ServiceCheck> {
+        self.selected_host_name
+            .as_deref()
+            .map(|host_name| self.get_host_services_status(host_name))
+            .unwrap_or_default()
+    }
+
+    /// The highlighted row of the host detail services table.
+    pub fn selected_detail_service(&self) -> Option<ServiceCheck> {
+        self.detail_services().into_iter().nth(self.detail_selected_index)
+    }
+
+    pub fn toggle_triage(&mut self) {
+        self.show_triage = !self.show_triage;
+        self.triage_selected_index = 0;
+    }
+
+    /// Whether the triage list is showing and takes navigation.
+    pub fn in_triage_list(&self) -> bool {
+        self.show_triage && !self.in_detail_view()
+    }
+
+    /// Down and degraded services across all hosts, worst first and then
+    /// longest in that state first.
+    pub fn triage_services(&self) -> Vec<ServiceCheck> {
+        let mut services: Vec<ServiceCheck> = self
+            .statuses
+            .values()
+            .filter(|s| matches!(s.status, ServiceStatus::Down | ServiceStatus::Degraded) && self.matches_filter(s))
+            .cloned()
+            .collect();
+        services.sort_by(|a, b| {
+            b.status
+                .severity()
+                .cmp(&a.status.severity())
+                .then_with(|| a.status_since.cmp(&b.status_since))
+                .then_with(|| a.host_name.cmp(&b.host_name))
+                .then_with(|| a.service_name.cmp(&b.service_name))
+        });
+        services
+    }
+
+    /// The highlighted row of the triage list.
+    pub fn selected_triage_service(&self) -> Option<ServiceCheck> {
+        self.triage_services().into_iter().nth(self.triage_selected_index)
+    }
+
+    /// Collapses or expands the selected host. On a service row this collapses
+    /// its host and moves the selection up to the header.
+    pub fn toggle_collapse(&mut self) {
+        if self.view_mode == ViewMode::Flat {
+            self.set_status_message("Hosts can't be collapsed in the flat view");
+            return;
+        }
+        
+        let host_name = match self.get_selected_item() {
+            Some(SelectedItem::HostHeader(host_name)) => host_name,
+            Some(SelectedItem::Service(service)) => service.host_name,
+            None => return,
+        };
+        
+        if !self.collapsed_hosts.remove(&host_name) {
+            self.collapsed_hosts.insert(host_name.clone());
+        }
+        
+        if let Some(row) = self.visible_rows().iter().position(
+            |item| matches!(item, SelectedItem::HostHeader(name) if *name == host_name)
+        ) {
+            self.selected_index = row;
+        }
+        self.sync_table_selection();
+    }
+
+    /// Toggles the acknowledgement of the selected service. Services that are
+    /// up have nothing to acknowledge.
+    pub fn toggle_acknowledged(&mut self) {
+        let selected = if self.show_triage {
+            self.selected_triage_service().map(|service| SelectedItem::Service(Box::new(service)))
+        } else {
+            self.get_selected_item()
+        };
+        let Some(SelectedItem::Service(service)) = selected else {
+            return;
+        };
+        
+        if !self.acknowledged.remove(&service.key) && service.status != ServiceStatus::Up {
+            self.acknowledged.insert(service.key);
+        }
+        self.rebaseline_down_count();
+    }
+
+    /// Switches checks of the selected service off or back on. On a host
+    /// header this applies to all its services: off if any are still on.
+    pub async fn toggle_enabled(&mut self) {
+        let services = match self.get_selected_item() {
+            Some(SelectedItem::HostHeader(host_name)) => self
+                .get_host_services_status(&host_name)
+                .into_iter()
+                .filter(|service| self.matches_filter(service))
+                .collect(),
+            Some(SelectedItem::Service(service)) => vec![*service],
+            None => return,
+        };
+        if services.is_empty() {
+            return;
+        }
+        
+        let enable = services.iter().all(|service| service.disabled);
+        for service in &services {
+            self.monitor_engine.set_enabled(&service.key, enable).await;
+        }
+        self.update_statuses().await;
+        
+        let target = match services.as_slice() {
+            [service] => format!("{}/{}", service.host_name, service.service_name),
+            _ => format!("{} services on {}", services.len(), services[0].host_name),
+        };
+        self.set_status_message(format!("{} checks of {}", if enable { "Enabled" } else { "Disabled" }, target));
+    }
+
+    /// The service the current view is focused on, if any: the one shown in
+    /// detail, or the highlighted row.
+    fn focused_service(&self) -> Option<ServiceCheck> {
+        if self.show_service_detail {
+            self.get_detail_service().cloned()
+        } else if self.show_host_detail {
+            self.selected_detail_service()
+        } else if self.show_triage {
+            self.selected_triage_service()
+        } else {
+            match self.get_selected_item() {
+                Some(SelectedItem::Service(check)) => Some(*check),
+                _ => None,
+            }
+        }
+    }
+
+    /// Opens the response time graph for the focused service, or for the
+    /// slowest shown services when none is focused; closes it if open.
+    pub fn toggle_graph(&mut self) {
+        if self.show_graph {
+            self.show_graph = false;
+            self.graph_keys.clear();
+            return;
+        }
+        
+        self.graph_keys = match self.focused_service() {
+            Some(check) => vec![check.key],
+            None => {
+                let mut averages: Vec<(Duration, String)> = self
+                    .statuses
+                    .values()
+                    .filter(|s| self.matches_filter(s))
+                    .filter_map(|s| {
+                        let times: Vec<Duration> = self.samples.get(&s.key)?.iter().filter_map(|sample| sample.response_time).collect();
+                        let average = times.iter().sum::<Duration>().checked_div(times.len() as u32)?;
+                        Some((average, s.key.clone()))
+                    })
+                    .collect();
+                averages.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+                averages.into_iter().take(GRAPH_TOP_SERVICES).map(|(_, key)| key).collect()
+            }
+        };
+        if self.graph_keys.is_empty() {
+            self.set_status_message("No response times to graph yet");
+            return;
+        }
+        self.show_graph = true;
+        self.show_events = false;
+    }
+
+    /// Opens or closes the status change log, starting at the newest entry.
+    pub fn toggle_events(&mut self) {
+        self.show_events = !self.show_events;
+        self.events_scroll = 0;
+        if self.show_events {
+            self.show_graph = false;
+        }
+    }
+
+    pub fn scroll_events(&mut self, lines: i32) {
+        let max = self.events.len().saturating_sub(1) as u16;
+        self.events_scroll = self.events_scroll.saturating_add_signed(lines as i16).min(max);
+    }
+
+    /// The services in the graph, in the order they were picked.
+    pub fn graph_services(&self) -> Vec<ServiceCheck> {
+        self.graph_keys.iter().filter_map(|key| self.statuses.get(key).cloned()).collect()
+    }
+
+    /// Starts typing a note for the focused service, beginning with its
+    /// current one.
+    pub fn start_note(&mut self) {
+        let Some(check) = self.focused_service() else {
+            self.set_status_message("Select a service to annotate");
+            return;
+        };
+        self.note_input = self.notes.get(&check.key).cloned().unwrap_or_default();
+        self.note_target = Some(check.key);
+    }
+
+    pub fn push_note_char(&mut self, c: char) {
+        self.note_input.push(c);
+    }
+
+    pub fn pop_note_char(&mut self) {
+        self.note_input.pop();
+    }
+
+    pub fn cancel_note(&mut self) {
+        self.note_target = None;
+        self.note_input.clear();
+    }
+
+    /// Stores the typed note on its service; an empty note clears it.
+    pub fn confirm_note(&mut self) {
+        let Some(key) = self.note_target.take() else {
+            return;
+        };
+        let note = std::mem::take(&mut self.note_input).trim().to_string();
+        let target = self
+            .statuses
+            .get(&key)
+            .map(|check| format!("{}/{}", check.host_name, check.service_name))
+            .unwrap_or_else(|| key.clone());
+        
+        let changed = if note.is_empty() {
+            self.notes.remove(&key).is_some()
+        } else {
+            self.notes.insert(key, note.clone()).as_ref() != Some(&note)
+        };
+        if !changed {
+            return;
+        }
+        
+        if let Some(path) = &self.config.settings.notes_file {
+            if let Err(e) = save_notes(Path::new(path), &self.notes) {
+                warn!("Failed to save notes: {:#}", e);
+                self.set_status_message(format!("Failed to save notes: {:#}", e));
+                return;
             }
         }
+        self.set_status_message(if note.is_empty() {
+            format!("Cleared note on {}", target)
+        } else {
+            format!("Noted on {}", target)
+        });
+    }
+
+    pub fn note(&self, key: &str) -> Option<&str> {
+        self.notes.get(key).map(String::as_str)
     }
 
-    pub fn exit_host_detail(&mut self) {
+    /// Saves the current statuses and recent samples to a timestamped file in
+    /// `snapshot_dir`, noting the outcome in the status bar.
+    pub fn save_snapshot(&mut self) {
+        let mut statuses: Vec<ServiceCheck> = self.statuses.values().cloned().collect();
+        statuses.sort_by(|a, b| a.host_name.cmp(&b.host_name).then_with(|| a.service_name.cmp(&b.service_name)));
+        
+        let settings = &self.config.settings;
+        match export::write_snapshot(Path::new(&settings.snapshot_dir), &settings.timezone, &statuses, &self.samples) {
+            Ok(path) => {
+                info!("Saved snapshot to {}", path.display());
+                self.set_status_message(format!("Saved snapshot to {}", path.display()));
+            }
+            Err(e) => {
+                warn!("Failed to save snapshot: {:#}", e);
+                self.set_status_message(format!("Failed to save snapshot: {:#}", e));
+            }
+        }
+    }
+
+    /// URL of the selected HTTP/HTTPS service. Leaves a status-bar note when
+    /// the selection has nothing to open.
+    pub fn selected_service_url(&mut self) -> Option<String> {
+        let Some(check) = self.focused_service() else {
+            self.set_status_message("Select a service to open its URL");
+            return None;
+        };
+        
+        let url = self
+            .find_service_config(&check)
+            .and_then(|(host, service)| service_url(&host.address, service));
+        
+        if url.is_none() {
+            self.set_status_message(format!("No URL to open for {} services", check.protocol));
+        }
+        url
+    }
+
+    pub fn set_status_message(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), Instant::now()));
+    }
+
+    /// The latest status-bar message, until it expires.
+    pub fn status_message(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < STATUS_MESSAGE_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    pub fn is_acknowledged(&self, key: &str) -> bool {
+        self.acknowledged.contains(key)
+    }
+
+    pub fn is_collapsed(&self, host_name: &str) -> bool {
+        self.collapsed_hosts.contains(host_name)
+    }
+
+    pub fn scroll_detail(&mut self, lines: i32) {
+        self.detail_scroll = self.detail_scroll.saturating_add_signed(lines as i16);
+    }
+
+    /// Leaves the detail view, going back to the host detail view first when
+    /// the service detail was opened from there.
+    pub fn exit_detail(&mut self) {
+        self.detail_scroll = 0;
+        if self.show_service_detail && self.show_host_detail {
+            self.show_service_detail = false;
+            self.selected_service_key = None;
+            return;
+        }
+        self.detail_selected_index = 0;
         self.show_host_detail = false;
         self.selected_host_name = None;
+        self.show_service_detail = false;
+        self.selected_service_key = None;
+    }
+
+    pub fn get_detail_service(&self) -> Option<&ServiceCheck> {
+        self.selected_service_key
+            .as_ref()
+            .and_then(|key| self.statuses.get(key))
+    }
+
+    /// The config entry a check result came from.
+    pub fn find_service_config(&self, check: &ServiceCheck) -> Option<(&crate::config::Host, &crate::config::Service)> {
+        let (host_index, service_index) = check.config_indices()?;
+        let host = self.config.hosts.get(host_index)?;
+        Some((host, host.services.get(service_index)?))
+    }
+
+    /// The service's configured icon, or the default for its protocol.
+    pub fn service_icon(&self, check: &ServiceCheck) -> &str {
+        self.find_service_config(check)
+            .and_then(|(_, service)| service.icon.as_deref())
+            .unwrap_or_else(|| self.status_style.protocol_icon(&check.protocol))
     }
 
     pub fn get_selected_host(&self) -> Option<&crate::config::Host> {
@@ -107,76 +957,382 @@ impl App {
     }
 
     pub fn get_host_services_status(&self, host_name: &str) -> Vec<ServiceCheck> {
-        self.statuses
+        let mut services: Vec<_> = self
+            .statuses
             .values()
             .filter(|status| status.host_name == host_name)
             .cloned()
-            .collect()
+            .collect();
+        services.sort_by(|a, b| a.service_name.cmp(&b.service_name).then_with(|| a.key.cmp(&b.key)));
+        services
+    }
+
+    pub fn start_filter(&mut self) {
+        self.filter_editing = true;
+    }
+
+    pub fn push_filter_char(&mut self, c: char) {
+        self.filter.push(c);
+        self.clamp_selection();
+    }
+
+    pub fn pop_filter_char(&mut self) {
+        self.filter.pop();
+        self.clamp_selection();
+    }
+
+    pub fn confirm_filter(&mut self) {
+        self.filter_editing = false;
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.filter_editing = false;
+        self.clamp_selection();
+    }
+
+    pub fn is_filter_active(&self) -> bool {
+        !self.filter.is_empty()
+    }
+
+    /// Steps the tag filter through every tag in the config, then back to none.
+    pub fn cycle_tag_filter(&mut self) {
+        let tags = self.config.all_tags();
+        if tags.is_empty() {
+            self.set_status_message("No tags in config");
+            return;
+        }
+        
+        let next = match &self.tag_filter {
+            None => 0,
+            Some(current) => tags.iter().position(|t| t == current).map_or(0, |i| i + 1),
+        };
+        self.tag_filter = tags.get(next).cloned();
+        self.clamp_selection();
+        self.rebaseline_down_count();
+    }
+
+    fn matches_tag(&self, status: &ServiceCheck) -> bool {
+        let Some(tag) = &self.tag_filter else {
+            return true;
+        };
+        self.find_service_config(status)
+            .is_some_and(|(host, service)| Config::has_tag(host, service, tag))
+    }
+
+    fn matches_filter(&self, status: &ServiceCheck) -> bool {
+        if !self.matches_tag(status) {
+            return false;
+        }
+        if self.filter.is_empty() {
+            return true;
+        }
+        let query = self.filter.to_lowercase();
+        status.host_name.to_lowercase().contains(&query)
+            || status.service_name.to_lowercase().contains(&query)
+    }
+
+    fn clamp_selection(&mut self) {
+        let total_items = self.get_total_items();
+        if self.selected_index >= total_items {
+            self.selected_index = total_items.saturating_sub(1);
+        }
+        self.sync_table_selection();
     }
 
     pub fn get_grouped_status_list(&self) -> Vec<(String, Vec<ServiceCheck>)> {
         let mut grouped: HashMap<String, Vec<ServiceCheck>> = HashMap::new();
         
-        // Group services by host
-        for status in self.statuses.values() {
+        // Group services by host, skipping anything hidden by the filter
+        for status in self.statuses.values().filter(|s| self.matches_filter(s)) {
             grouped
                 .entry(status.host_name.clone())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(status.clone());
         }
         
-        // Sort hosts and services within each host
+        // Sort services within each host, then order hosts by their first service
         let mut result: Vec<_> = grouped.into_iter().collect();
-        result.sort_by(|(a_host, _), (b_host, _)| a_host.cmp(b_host));
-        
         for (_, services) in &mut result {
-            services.sort_by(|a, b| a.service_name.cmp(&b.service_name));
+            services.sort_by(|a, b| {
+                self.sort_mode
+                    .compare(a, b)
+                    .then_with(|| a.service_name.cmp(&b.service_name))
+                    .then_with(|| a.key.cmp(&b.key))
+            });
         }
         
+        result.sort_by(|(a_host, a_services), (b_host, b_services)| {
+            let by_mode = match (a_services.first(), b_services.first()) {
+                (Some(a), Some(b)) => self.sort_mode.compare(a, b),
+                _ => Ordering::Equal,
+            };
+            let by_host_order = match self.host_order {
+                HostOrder::Alpha => Ordering::Equal,
+                HostOrder::Config => host_config_index(a_services).cmp(&host_config_index(b_services)),
+                HostOrder::Status => self
+                    .get_host_rollup(b_host)
+                    .severity()
+                    .cmp(&self.get_host_rollup(a_host).severity()),
+            };
+            by_mode.then(by_host_order).then_with(|| a_host.cmp(b_host))
+        });
+        
         result
     }
 
-    pub fn get_selected_item(&self) -> Option<SelectedItem> {
-        let grouped = self.get_grouped_status_list();
+    /// Rows of the services table in display order: each host header followed
+    /// by its services unless the host is collapsed. The flat view has only
+    /// the services, sorted across hosts.
+    pub fn visible_rows(&self) -> Vec<SelectedItem> {
+        if self.view_mode == ViewMode::Flat {
+            let mut services: Vec<ServiceCheck> = self.get_grouped_status_list().into_iter().flat_map(|(_, services)| services).collect();
+            // Stable, so hosts keep their order among equals
+            services.sort_by(|a, b| self.sort_mode.compare(a, b));
+            return services.into_iter().map(|service| SelectedItem::Service(Box::new(service))).collect();
+        }
         
-        // Only host headers are selectable, so just return the host at selected_index
-        if let Some((host_name, _)) = grouped.get(self.selected_index) {
-            Some(SelectedItem::HostHeader(host_name.clone()))
-        } else {
-            None
+        let mut rows = Vec::new();
+        for (host_name, services) in self.get_grouped_status_list() {
+            let collapsed = self.is_collapsed(&host_name);
+            rows.push(SelectedItem::HostHeader(host_name));
+            if !collapsed {
+                rows.extend(services.into_iter().map(|service| SelectedItem::Service(Box::new(service))));
+            }
         }
+        rows
+    }
+
+    pub fn get_selected_item(&self) -> Option<SelectedItem> {
+        self.visible_rows().into_iter().nth(self.selected_index)
     }
 
     pub fn get_total_items(&self) -> usize {
-        // Only count host headers as selectable items
-        self.get_grouped_status_list().len()
+        self.visible_rows().len()
+    }
+
+    /// Percentiles of the service's recent response times, ignoring checks
+    /// that got no answer. `None` until there are enough samples.
+    pub fn response_percentiles(&self, key: &str) -> Option<ResponsePercentiles> {
+        let mut times: Vec<Duration> = self
+            .samples
+            .get(key)?
+            .iter()
+            .filter(|sample| matches!(sample.status, ServiceStatus::Up | ServiceStatus::Degraded))
+            .filter_map(|sample| sample.response_time)
+            .collect();
+        if times.len() < MIN_PERCENTILE_SAMPLES {
+            return None;
+        }
+        times.sort();
+
+        // Nearest-rank percentile
+        let rank = |p: usize| times[(p * times.len()).div_ceil(100).max(1) - 1];
+        Some(ResponsePercentiles {
+            samples: times.len(),
+            p50: rank(50),
+            p95: rank(95),
+            p99: rank(99),
+        })
+    }
+
+    /// Up and total counts over the service's recent checks. Maintenance and
+    /// inconclusive results are left out; degraded counts as up.
+    pub fn availability_counts(&self, key: &str) -> (usize, usize) {
+        let Some(samples) = self.samples.get(key) else {
+            return (0, 0);
+        };
+        let counted: Vec<_> = samples
+            .iter()
+            .filter(|sample| matches!(sample.status, ServiceStatus::Up | ServiceStatus::Degraded | ServiceStatus::Down))
+            .collect();
+        let up = counted.iter().filter(|sample| sample.status != ServiceStatus::Down).count();
+        (up, counted.len())
+    }
+
+    /// Percentage of recent checks that were up across the shown services,
+    /// combined per `availability_weighting`. `None` before any check counts.
+    pub fn fleet_availability(&self) -> Option<f64> {
+        let counts: Vec<(usize, usize)> = self
+            .statuses
+            .values()
+            .filter(|s| self.matches_tag(s))
+            .map(|s| self.availability_counts(&s.key))
+            .filter(|&(_, total)| total > 0)
+            .collect();
+        if counts.is_empty() {
+            return None;
+        }
+        let ratio = match self.availability_weighting {
+            AvailabilityWeighting::Service => {
+                counts.iter().map(|&(up, total)| up as f64 / total as f64).sum::<f64>() / counts.len() as f64
+            }
+            AvailabilityWeighting::Checks => {
+                let up: usize = counts.iter().map(|&(up, _)| up).sum();
+                let total: usize = counts.iter().map(|&(_, total)| total).sum();
+                up as f64 / total as f64
+            }
+        };
+        Some(ratio * 100.0)
+    }
+
+    /// Worst status among all of a host's services.
+    pub fn get_host_rollup(&self, host_name: &str) -> ServiceStatus {
+        self.statuses
+            .values()
+            .filter(|status| status.host_name == host_name)
+            .map(|status| status.status.clone())
+            .max_by_key(|status| status.severity())
+            .unwrap_or(ServiceStatus::Unknown)
     }
 
-    pub fn get_summary_stats(&self) -> (usize, usize, usize) {
-        let mut up = 0;
-        let mut down = 0;
-        let mut unknown = 0;
+    pub fn get_summary_stats(&self) -> SummaryStats {
+        let mut stats = SummaryStats::default();
 
-        for status in self.statuses.values() {
+        for status in self.statuses.values().filter(|s| self.matches_tag(s)) {
+            if self.is_acknowledged(&status.key) {
+                stats.acknowledged += 1;
+                continue;
+            }
             match status.status {
-                crate::monitor::ServiceStatus::Up => up += 1,
-                crate::monitor::ServiceStatus::Down => down += 1,
-                crate::monitor::ServiceStatus::Unknown => unknown += 1,
+                ServiceStatus::Up => stats.up += 1,
+                ServiceStatus::Degraded => stats.degraded += 1,
+                ServiceStatus::Down => stats.down += 1,
+                ServiceStatus::Unknown => stats.unknown += 1,
+                ServiceStatus::Pending => stats.pending += 1,
+                ServiceStatus::Maintenance => stats.maintenance += 1,
             }
         }
 
-        (up, down, unknown)
+        stats
+    }
+
+    /// Classifies each host from its services' statuses. Unknown and pending
+    /// results are left out, and hosts with nothing else yet aren't counted.
+    pub fn get_host_summary(&self) -> HostSummary {
+        let mut by_host: HashMap<&str, Vec<&ServiceStatus>> = HashMap::new();
+        for status in self.statuses.values().filter(|s| self.matches_tag(s) && !matches!(s.status, ServiceStatus::Unknown | ServiceStatus::Pending)) {
+            by_host.entry(status.host_name.as_str()).or_default().push(&status.status);
+        }
+        
+        let mut summary = HostSummary::default();
+        for statuses in by_host.values() {
+            if statuses.iter().all(|status| **status == ServiceStatus::Down) {
+                summary.down += 1;
+            } else if statuses.iter().all(|status| matches!(status, ServiceStatus::Up | ServiceStatus::Maintenance)) {
+                summary.healthy += 1;
+            } else {
+                summary.degraded += 1;
+            }
+        }
+        summary
     }
 
     pub fn get_total_services(&self) -> usize {
-        self.statuses.len()
+        self.statuses.values().filter(|s| self.matches_tag(s)).count()
     }
 
     pub fn get_host_count(&self) -> usize {
-        self.config.hosts.len()
+        match &self.tag_filter {
+            Some(tag) => self
+                .config
+                .hosts
+                .iter()
+                .filter(|host| host.services.iter().any(|service| Config::has_tag(host, service, tag)))
+                .count(),
+            None => self.config.hosts.len(),
+        }
+    }
+
+    /// Age of the newest result when it is older than the engine should allow,
+    /// which usually means the checks have stopped running.
+    pub fn stale_data_age(&self) -> Option<Duration> {
+        if self.paused {
+            return None;
+        }
+        
+        // Allow for a slow check timing out before its result lands
+        let slowest_check = self
+            .config
+            .hosts
+            .iter()
+            .flat_map(|host| &host.services)
+            .map(|service| service.timeout)
+            .max()
+            .unwrap_or(0);
+        let threshold = Duration::from_secs(self.config.min_refresh_interval() * STALE_INTERVALS + slowest_check);
+        let age = (Utc::now() - self.monitor_engine.last_result_at()).to_std().ok()?;
+        (age > threshold).then_some(age)
+    }
+
+    /// Whether the slowest check takes most of its refresh interval, so
+    /// checks are about to run back to back.
+    pub fn falling_behind(&self) -> bool {
+        self.check_timing
+            .slowest
+            .as_ref()
+            .is_some_and(|(_, duration, interval)| duration.as_secs_f64() >= interval.as_secs_f64() * FALLING_BEHIND_RATIO)
     }
 
     pub fn get_refresh_interval(&self) -> Duration {
         Duration::from_secs(self.config.settings.refresh_interval)
     }
-} 
\ No newline at end of file
+}
+
+/// Takes over a peer's result where it is worse than the local one, so a
+/// service down from any vantage point shows as down. Only degraded and down
+/// results count; a peer not knowing doesn't make a service look worse.
+fn roll_up_peers(mut statuses: StatusSnapshot, peer_results: &PeerSnapshot) -> StatusSnapshot {
+    if peer_results.is_empty() {
+        return statuses;
+    }
+    
+    let worse: Vec<ServiceCheck> = statuses
+        .values()
+        .filter(|check| !check.disabled)
+        .filter_map(|check| {
+            let (vantage, peer_check) = peers::results_for(peer_results, check)
+                .into_iter()
+                .filter_map(|(name, peer_check)| Some((name, peer_check?)))
+                .filter(|(_, peer_check)| matches!(peer_check.status, ServiceStatus::Degraded | ServiceStatus::Down))
+                .max_by_key(|(_, peer_check)| peer_check.status.severity())?;
+            (peer_check.status.severity() > check.status.severity()).then(|| ServiceCheck {
+                status: peer_check.status.clone(),
+                status_since: peer_check.status_since,
+                error_message: peer_check.error_message.clone(),
+                detail: peer_check.detail.clone(),
+                vantage: Some(vantage.to_string()),
+                ..check.clone()
+            })
+        })
+        .collect();
+    
+    if !worse.is_empty() {
+        let map = Arc::make_mut(&mut statuses);
+        for check in worse {
+            map.insert(check.key.clone(), check);
+        }
+    }
+    statuses
+}
+
+/// Position of the host in the config file, recovered from a service key.
+fn host_config_index(services: &[ServiceCheck]) -> usize {
+    services
+        .first()
+        .and_then(|service| service.config_indices())
+        .map_or(usize::MAX, |(host_index, _)| host_index)
+}
+
+fn load_notes(path: &Path) -> Result<HashMap<String, String>> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read notes file: {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("Failed to parse notes file: {}", path.display()))
+}
+
+fn save_notes(path: &Path, notes: &HashMap<String, String>) -> Result<()> {
+    // Sorted, so the file diffs cleanly between saves
+    let notes: std::collections::BTreeMap<_, _> = notes.iter().collect();
+    let json = serde_json::to_string_pretty(&notes).with_context(|| "Failed to serialize notes")?;
+    std::fs::write(path, json).with_context(|| format!("Failed to write notes file: {}", path.display()))
+}
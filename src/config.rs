@@ -1,11 +1,22 @@
-use anyhow::{Context, Result};
+use crate::json_assert::JsonAssertion;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, NaiveDateTime, NaiveTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
+    /// Other config files whose hosts are merged into this one. Relative
+    /// paths resolve against the including file's directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(default)]
     pub hosts: Vec<Host>,
+    #[serde(default)]
     pub settings: Settings,
 }
 
@@ -15,22 +26,259 @@ pub struct Host {
     pub address: String,
     #[serde(default)]
     pub description: Option<String>,
+    /// Labels applied to every service on this host, for filtering.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     pub services: Vec<Service>,
     #[serde(default = "default_timeout")]
     pub timeout: u64,
+    /// Overrides `settings.refresh_interval` for every service on this host.
+    #[serde(default)]
+    pub refresh_interval: Option<u64>,
+    /// Reach this host's HTTP services directly, never through the proxies.
+    #[serde(default)]
+    pub bypass_proxy: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Service {
     pub name: String,
+    /// Inferred from the protocol at load time when omitted.
+    #[serde(default)]
     pub port: u16,
     pub protocol: Protocol,
+    /// Labels for filtering, in addition to the host's tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Disabled services are never checked and don't appear in results.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
     #[serde(default)]
     pub path: Option<String>,
     #[serde(default)]
     pub description: Option<String>,
+    /// Shown before the name in the services table; defaults to one for the
+    /// protocol.
+    #[serde(default)]
+    pub icon: Option<String>,
     #[serde(default = "default_service_timeout")]
     pub timeout: u64,
+    /// Seconds allowed to establish the connection; defaults to the shorter of
+    /// `timeout` and 5s so dead hosts fail fast.
+    #[serde(default)]
+    pub connect_timeout: Option<u64>,
+    /// Seconds allowed for the response once connected; defaults to `timeout`.
+    #[serde(default)]
+    pub read_timeout: Option<u64>,
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    /// Consecutive failed checks before the service is reported down.
+    #[serde(default = "default_threshold")]
+    pub failure_threshold: u32,
+    /// Consecutive successful checks before a down service is reported up again.
+    #[serde(default = "default_threshold")]
+    pub success_threshold: u32,
+    /// Response time in milliseconds above which a check counts as slow.
+    #[serde(default)]
+    pub warn_response_ms: Option<u64>,
+    /// Consecutive slow checks before the service is reported degraded.
+    #[serde(default = "default_slow_threshold")]
+    pub slow_threshold: u32,
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub grpc_service: Option<String>,
+    /// Send a ping after the WebSocket handshake and wait for the pong.
+    #[serde(default)]
+    pub ws_ping: bool,
+    /// Prefix the greeting must start with for `banner` services, e.g. `220` or `SSH-`.
+    #[serde(default)]
+    pub expected_banner: Option<String>,
+    /// Bytes a `tcp` check writes after connecting; escapes like `\r\n` are allowed.
+    #[serde(default)]
+    pub send: Option<String>,
+    /// Bytes the `tcp` check's response must contain, e.g. `+PONG`.
+    #[serde(default)]
+    pub expect: Option<String>,
+    /// After a `tcp` connect, wait briefly to make sure the connection isn't
+    /// reset at once, as when a forwarded port has no backend behind it.
+    #[serde(default)]
+    pub verify_open: bool,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Response headers an `http`/`https` check records for the detail
+    /// view, e.g. `Server` or `X-Version`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capture_headers: Vec<String>,
+    #[serde(default)]
+    pub basic_auth: Option<BasicAuth>,
+    /// PEM client certificate and PKCS#8 key for mutual TLS; overrides the
+    /// ones in settings.
+    #[serde(default)]
+    pub client_cert: Option<String>,
+    #[serde(default)]
+    pub client_key: Option<String>,
+    #[serde(default = "default_follow_redirects")]
+    pub follow_redirects: bool,
+    /// Speak HTTP/2 from the first byte instead of negotiating, for servers
+    /// that only accept HTTP/2.
+    #[serde(default)]
+    pub http2: bool,
+    #[serde(default)]
+    pub method: HttpMethod,
+    /// Request body for `http`/`https` checks; ignored for GET and HEAD.
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Assertion on the JSON response body, e.g. `$.status == "ok"`.
+    #[serde(default)]
+    pub json_assert: Option<JsonAssertion>,
+    /// Overrides the host and global refresh interval for this service.
+    #[serde(default)]
+    pub refresh_interval: Option<u64>,
+    /// Name of another service on the same host; while it is down this one
+    /// isn't checked and shows as unknown.
+    #[serde(default)]
+    pub depends_on: Option<String>,
+    /// Keep connections open between checks: TCP checks reuse one socket and
+    /// HTTP checks reuse pooled connections.
+    #[serde(default)]
+    pub keep_alive: bool,
+    /// Scheduled windows during which the service is not checked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maintenance: Vec<MaintenanceWindow>,
+}
+
+/// Most response headers a service records, so a long list can't grow the
+/// stored results without bound.
+pub const MAX_CAPTURED_HEADERS: usize = 16;
+
+// Upper bound on the default connect timeout
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 5;
+
+impl Service {
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.connect_timeout
+                .unwrap_or_else(|| self.timeout.min(DEFAULT_CONNECT_TIMEOUT_SECS)),
+        )
+    }
+
+    pub fn read_timeout(&self) -> Duration {
+        Duration::from_secs(self.read_timeout.unwrap_or(self.timeout))
+    }
+
+    /// How long a failing check runs: every attempt using up `timeout`,
+    /// plus the delays between retries.
+    pub fn max_check_duration(&self) -> Duration {
+        Duration::from_secs(self.timeout) * (self.retries + 1) + Duration::from_millis(self.retry_delay_ms) * self.retries
+    }
+
+    /// Client certificate and key paths for mutual TLS, from the service if
+    /// it sets either, otherwise from settings.
+    pub fn client_identity<'a>(&'a self, settings: &'a Settings) -> Option<(&'a str, &'a str)> {
+        if self.client_cert.is_some() || self.client_key.is_some() {
+            Some((self.client_cert.as_deref()?, self.client_key.as_deref()?))
+        } else {
+            Some((settings.client_cert.as_deref()?, settings.client_key.as_deref()?))
+        }
+    }
+
+    /// Whether `now` falls inside any maintenance window, evaluated in
+    /// `timezone` (UTC if it doesn't parse).
+    pub fn in_maintenance(&self, now: DateTime<Utc>, timezone: &str) -> bool {
+        if self.maintenance.is_empty() {
+            return false;
+        }
+        let local = match timezone.parse::<chrono_tz::Tz>() {
+            Ok(tz) => now.with_timezone(&tz).naive_local(),
+            Err(_) => now.naive_utc(),
+        };
+        self.maintenance.iter().any(|window| window.contains(local))
+    }
+}
+
+/// A daily time range, optionally limited to some weekdays. A range whose
+/// end is before its start runs past midnight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// Start time, "HH:MM".
+    pub start: String,
+    /// End time, "HH:MM" (exclusive).
+    pub end: String,
+    /// Days the window starts on; every day when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Weekday>,
+}
+
+impl MaintenanceWindow {
+    fn times(&self) -> Result<(NaiveTime, NaiveTime)> {
+        let parse = |value: &str| {
+            NaiveTime::parse_from_str(value, "%H:%M")
+                .with_context(|| format!("invalid time '{}', expected HH:MM", value))
+        };
+        Ok((parse(&self.start)?, parse(&self.end)?))
+    }
+
+    fn starts_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    pub fn contains(&self, local: NaiveDateTime) -> bool {
+        let Ok((start, end)) = self.times() else {
+            return false;
+        };
+        let time = local.time();
+        let today = local.weekday();
+
+        if start <= end {
+            start <= time && time < end && self.starts_on(today)
+        } else if time >= start {
+            self.starts_on(today)
+        } else {
+            // Early-morning part of a window that started yesterday
+            time < end && self.starts_on(today.pred())
+        }
+    }
+}
+
+/// Another daystrom instance whose status API results are merged into this
+/// one's view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Peer {
+    /// Vantage point its results are shown under, e.g. `eu-west`.
+    pub name: String,
+    /// Its status API endpoint, e.g. `http://monitor-eu:9200/status`.
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasicAuth {
+    pub username: String,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    #[default]
+    Get,
+    Head,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Options,
+}
+
+impl HttpMethod {
+    pub fn allows_body(self) -> bool {
+        !matches!(self, HttpMethod::Get | HttpMethod::Head)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +288,30 @@ pub enum Protocol {
     Udp,
     Http,
     Https,
+    Dns,
+    Grpc,
+    Banner,
+    Ws,
+    Wss,
+    /// A Unix domain socket at the host's `address`; the port is ignored.
+    Unix,
+}
+
+impl Protocol {
+    /// The well-known port for protocols where one is unambiguous.
+    pub fn default_port(&self) -> Option<u16> {
+        match self {
+            Protocol::Http | Protocol::Ws => Some(80),
+            Protocol::Https | Protocol::Wss => Some(443),
+            Protocol::Dns => Some(53),
+            Protocol::Tcp | Protocol::Udp | Protocol::Grpc | Protocol::Banner | Protocol::Unix => None,
+        }
+    }
+
+    /// Whether services of this protocol connect to a port.
+    pub fn uses_port(&self) -> bool {
+        !matches!(self, Protocol::Unix)
+    }
 }
 
 impl std::fmt::Display for Protocol {
@@ -49,6 +321,12 @@ impl std::fmt::Display for Protocol {
             Protocol::Udp => write!(f, "udp"),
             Protocol::Http => write!(f, "http"),
             Protocol::Https => write!(f, "https"),
+            Protocol::Dns => write!(f, "dns"),
+            Protocol::Grpc => write!(f, "grpc"),
+            Protocol::Banner => write!(f, "banner"),
+            Protocol::Ws => write!(f, "ws"),
+            Protocol::Wss => write!(f, "wss"),
+            Protocol::Unix => write!(f, "unix"),
         }
     }
 }
@@ -63,6 +341,334 @@ pub struct Settings {
     pub theme: String,
     #[serde(default = "default_timezone")]
     pub timezone: String,
+    /// Status glyphs: "emoji", "ascii" or "nerdfont".
+    #[serde(default = "default_status_style")]
+    pub status_style: String,
+    /// Coloring of down and degraded rows in the services table: "none",
+    /// "text" or "background".
+    #[serde(default = "default_row_highlight")]
+    pub row_highlight: String,
+    /// Decimal places for response times; unset shows whole µs/ms and
+    /// seconds to two places.
+    #[serde(default)]
+    pub response_time_decimals: Option<usize>,
+    /// Grouping separator for large counts in the stats panel; empty for none.
+    #[serde(default = "default_thousands_separator")]
+    pub thousands_separator: String,
+    /// Services table layout: "grouped" under host headers or "flat" with a
+    /// host column.
+    #[serde(default = "default_view_mode")]
+    pub view_mode: String,
+    /// Host ordering: "alpha", "config" (file order) or "status" (problems first).
+    #[serde(default = "default_host_order")]
+    pub host_order: String,
+    /// How fleet availability combines services: "service" (each service
+    /// counts equally) or "checks" (every recent check counts equally).
+    #[serde(default = "default_availability_weighting")]
+    pub availability_weighting: String,
+    #[serde(default = "default_max_concurrent_checks")]
+    pub max_concurrent_checks: usize,
+    /// Upper bound on a random delay before each service's first check, so
+    /// services sharing an interval don't all fire at once. 0 disables.
+    #[serde(default)]
+    pub check_jitter_ms: u64,
+    /// Spread the first checks evenly over each service's interval instead of
+    /// starting them all at once.
+    #[serde(default)]
+    pub startup_ramp: bool,
+    #[serde(default)]
+    pub metrics_port: Option<u16>,
+    /// Serves the status snapshot as JSON at `/status` on this port.
+    #[serde(default)]
+    pub api_port: Option<u16>,
+    /// Other instances whose `/status` results are pulled every refresh
+    /// interval and rolled up with the local ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub peers: Vec<Peer>,
+    #[serde(default)]
+    pub notifications: bool,
+    /// Ring the terminal bell when another service goes down.
+    #[serde(default)]
+    pub audible_alerts: bool,
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Minimum seconds between alerts for one service; recoveries always go
+    /// out. 0 disables the limit.
+    #[serde(default)]
+    pub alert_cooldown: u64,
+    #[serde(default = "default_cert_warn_days")]
+    pub cert_warn_days: u32,
+    /// `User-Agent` sent by HTTP checks; defaults to `daystrom-tui/<version>`.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Skip TLS certificate verification, for internal services with
+    /// self-signed certificates.
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    /// Local IP address that outgoing check connections are made from, to
+    /// test reachability over a particular interface.
+    #[serde(default)]
+    pub bind_address: Option<String>,
+    /// Seconds a host name resolved for `tcp`, `udp`, `banner`, `dns` and
+    /// `grpc` checks is reused before resolving again. 0 resolves every check.
+    #[serde(default)]
+    pub dns_cache_ttl: u64,
+    /// Proxy for plain `http` checks and other HTTP requests. When neither
+    /// proxy is set, the HTTP_PROXY/HTTPS_PROXY/NO_PROXY environment
+    /// variables apply instead.
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+    /// Proxy for `https` checks.
+    #[serde(default)]
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts, domains and CIDR ranges reached directly
+    /// rather than through the configured proxies.
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+    /// Largest HTTP response body a check accepts, in bytes; bigger ones are
+    /// cut off and reported as degraded. 0 disables the limit.
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: u64,
+    /// Default client certificate and key for mutual TLS on `https` checks.
+    #[serde(default)]
+    pub client_cert: Option<String>,
+    #[serde(default)]
+    pub client_key: Option<String>,
+    #[serde(default)]
+    pub history_db: Option<String>,
+    /// Where the latest statuses are saved, so a restart shows them at once.
+    #[serde(default)]
+    pub state_file: Option<String>,
+    /// JSON file notes added with `n` are kept in across restarts.
+    #[serde(default)]
+    pub notes_file: Option<String>,
+    /// Directory the `w` key saves status snapshots to.
+    #[serde(default = "default_snapshot_dir")]
+    pub snapshot_dir: String,
+    /// Status changes within the last `flap_window` checks above which a
+    /// service counts as flapping and its alerts are held back. 0 disables.
+    #[serde(default = "default_flap_threshold")]
+    pub flap_threshold: u32,
+    #[serde(default = "default_flap_window")]
+    pub flap_window: usize,
+    /// How often the UI polls for new results and input, in milliseconds.
+    #[serde(default = "default_ui_tick_ms")]
+    pub ui_tick_ms: u64,
+}
+
+/// Expands `${VAR}` and `${VAR:-default}` references in raw config content
+/// so one file can be reused across environments. Comment lines are left alone.
+pub fn expand_env_vars(content: &str) -> Result<String> {
+    let mut result = String::with_capacity(content.len());
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if line.trim_start().starts_with('#') {
+            result.push_str(line);
+            continue;
+        }
+
+        let expanded = interpolate_env(line).with_context(|| {
+            format!("Failed to expand field '{}' on line {}", field_name(line), index + 1)
+        })?;
+        result.push_str(&expanded);
+    }
+
+    Ok(result)
+}
+
+/// Replaces every `${VAR}` or `${VAR:-default}` in `value`.
+pub fn interpolate_env(value: &str) -> Result<String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| anyhow::anyhow!("unterminated '${{' in value"))?;
+        let reference = &after[..end];
+        let resolved = match reference.split_once(":-") {
+            Some((name, default)) => std::env::var(name).unwrap_or_else(|_| default.to_string()),
+            None => std::env::var(reference)
+                .with_context(|| format!("environment variable '{}' is not set", reference))?,
+        };
+        result.push_str(&resolved);
+        rest = &after[end + 1..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// The config as a generic tree, or `None` if it doesn't parse.
+fn parse_tree(content: &str, format: ConfigFormat) -> Option<serde_json::Value> {
+    match format {
+        ConfigFormat::Yaml => serde_yaml::from_str(content).ok(),
+        ConfigFormat::Toml => toml::from_str(content).ok(),
+        ConfigFormat::Json => serde_json::from_str(content).ok(),
+    }
+}
+
+fn uses_templates(tree: &serde_json::Value) -> bool {
+    tree.get("service_templates").is_some()
+        || tree
+            .get("hosts")
+            .and_then(serde_json::Value::as_array)
+            .is_some_and(|hosts| hosts.iter().any(|host| host.get("use_template").is_some()))
+}
+
+/// Replaces each host's `use_template` with the services of the named
+/// templates, in order. The host's own services are merged over template
+/// services with the same name and added after the rest.
+fn expand_templates(tree: &mut serde_json::Value) -> Result<()> {
+    use serde_json::Value;
+
+    let Some(root) = tree.as_object_mut() else {
+        return Ok(());
+    };
+    let templates = match root.remove("service_templates") {
+        Some(Value::Object(templates)) => templates,
+        None | Some(Value::Null) => serde_json::Map::new(),
+        Some(_) => bail!("service_templates must map template names to lists of services"),
+    };
+    let Some(hosts) = root.get_mut("hosts").and_then(Value::as_array_mut) else {
+        return Ok(());
+    };
+
+    for host in hosts.iter_mut().filter_map(Value::as_object_mut) {
+        let host_name = host.get("name").and_then(Value::as_str).unwrap_or("?").to_string();
+        let names: Vec<String> = match host.remove("use_template") {
+            None | Some(Value::Null) => continue,
+            Some(Value::String(name)) => vec![name],
+            Some(Value::Array(names)) => names
+                .iter()
+                .map(|name| name.as_str().map(str::to_string))
+                .collect::<Option<_>>()
+                .with_context(|| format!("host '{}': use_template must name templates", host_name))?,
+            Some(_) => bail!("host '{}': use_template must be a template name or a list of them", host_name),
+        };
+
+        let mut services = Vec::new();
+        for name in &names {
+            let template = templates
+                .get(name)
+                .with_context(|| format!("host '{}' uses unknown service template '{}'", host_name, name))?
+                .as_array()
+                .with_context(|| format!("service template '{}' must be a list of services", name))?;
+            for service in template {
+                merge_service(&mut services, service.clone());
+            }
+        }
+        match host.remove("services") {
+            Some(Value::Array(own)) => own.into_iter().for_each(|service| merge_service(&mut services, service)),
+            None | Some(Value::Null) => {}
+            Some(_) => bail!("host '{}': services must be a list", host_name),
+        }
+        host.insert("services".to_string(), Value::Array(services));
+    }
+
+    Ok(())
+}
+
+/// Adds `service` to `services`, merged over an earlier one of the same name.
+fn merge_service(services: &mut Vec<serde_json::Value>, service: serde_json::Value) {
+    let name = service.get("name").cloned();
+    match services.iter_mut().find(|existing| name.is_some() && existing.get("name") == name.as_ref()) {
+        Some(existing) => merge_values(existing, service),
+        None => services.push(service),
+    }
+}
+
+/// Overwrites `base` with `overlay`, recursing into maps so a partial
+/// override keeps the rest of the template's fields.
+fn merge_values(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Best-effort name of the key on a config line, for error messages.
+fn field_name(line: &str) -> &str {
+    let line = line.trim().trim_start_matches("- ");
+    let key = line
+        .split_once([':', '='])
+        .map_or(line, |(key, _)| key);
+    key.trim().trim_matches('"')
+}
+
+/// Loads a PEM client certificate and its PKCS#8 PEM key for mutual TLS.
+pub fn load_identity(cert_path: &str, key_path: &str) -> Result<reqwest::Identity> {
+    let cert = fs::read(cert_path).with_context(|| format!("Failed to read client certificate: {}", cert_path))?;
+    let key = fs::read(key_path).with_context(|| format!("Failed to read client key: {}", key_path))?;
+    reqwest::Identity::from_pkcs8_pem(&cert, &key)
+        .with_context(|| format!("Invalid client certificate or key: {} / {}", cert_path, key_path))
+}
+
+/// Turns `\r`, `\n`, `\t`, `\0`, `\\` and `\xNN` escapes in a payload
+/// string into the bytes they stand for.
+pub fn unescape(value: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buffer = [0u8; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('r') => bytes.push(b'\r'),
+            Some('n') => bytes.push(b'\n'),
+            Some('t') => bytes.push(b'\t'),
+            Some('0') => bytes.push(0),
+            Some('\\') => bytes.push(b'\\'),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let byte = u8::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == 2)
+                    .with_context(|| format!("invalid escape '\\x{}' in '{}'", hex, value))?;
+                bytes.push(byte);
+            }
+            Some(other) => bail!("unknown escape '\\{}' in '{}'", other, value),
+            None => bail!("trailing backslash in '{}'", value),
+        }
+    }
+
+    Ok(bytes)
+}
+
+fn identity_problem(cert: &Option<String>, key: &Option<String>) -> Option<String> {
+    match (cert, key) {
+        (Some(cert), Some(key)) => load_identity(cert, key).err().map(|e| format!("{:#}", e)),
+        (None, None) => None,
+        _ => Some("client_cert and client_key must be set together".to_string()),
+    }
+}
+
+fn address_problem(address: &str) -> Option<&'static str> {
+    if address.trim().is_empty() {
+        Some("address is empty")
+    } else if address.contains("://") {
+        Some("address should be a hostname or IP, not a URL")
+    } else if address.chars().any(char::is_whitespace) {
+        Some("address contains whitespace")
+    } else if address.contains('/') {
+        Some("address should not contain a path")
+    } else {
+        None
+    }
 }
 
 fn default_timeout() -> u64 {
@@ -73,10 +679,58 @@ fn default_service_timeout() -> u64 {
     10
 }
 
+fn default_retry_delay_ms() -> u64 {
+    500
+}
+
+fn default_snapshot_dir() -> String {
+    ".".to_string()
+}
+
+fn default_threshold() -> u32 {
+    1
+}
+
+fn default_slow_threshold() -> u32 {
+    5
+}
+
+fn default_follow_redirects() -> bool {
+    true
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
 fn default_refresh_interval() -> u64 {
     5
 }
 
+fn default_max_concurrent_checks() -> usize {
+    50
+}
+
+fn default_cert_warn_days() -> u32 {
+    14
+}
+
+fn default_flap_threshold() -> u32 {
+    5
+}
+
+fn default_flap_window() -> usize {
+    10
+}
+
+fn default_ui_tick_ms() -> u64 {
+    250
+}
+
+fn default_max_response_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
 fn default_theme() -> String {
     "default".to_string()
 }
@@ -85,20 +739,454 @@ fn default_timezone() -> String {
     "UTC".to_string()
 }
 
+fn default_thousands_separator() -> String {
+    ",".to_string()
+}
+
+fn default_status_style() -> String {
+    "emoji".to_string()
+}
+
+fn default_row_highlight() -> String {
+    "none".to_string()
+}
+
+fn default_view_mode() -> String {
+    "grouped".to_string()
+}
+
+fn default_host_order() -> String {
+    "alpha".to_string()
+}
+
+fn default_availability_weighting() -> String {
+    "service".to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase());
+
+        match extension.as_deref() {
+            Some("yaml") | Some("yml") => Ok(ConfigFormat::Yaml),
+            Some("toml") => Ok(ConfigFormat::Toml),
+            Some("json") => Ok(ConfigFormat::Json),
+            _ => bail!(
+                "Unsupported config file extension for {} (expected .yaml, .yml, .toml or .json)",
+                path.display()
+            ),
+        }
+    }
+}
+
 impl Config {
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = fs::read_to_string(path.as_ref())
-            .with_context(|| format!("Failed to read config file: {}", path.as_ref().display()))?;
+        Self::load_with_includes(path.as_ref(), &mut Vec::new(), &mut HashMap::new())
+    }
+
+    /// Loads a file and merges in the hosts of everything it includes.
+    /// `stack` holds the files currently being loaded, to catch include cycles,
+    /// and `host_sources` records which file defined each host.
+    fn load_with_includes(
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+        host_sources: &mut HashMap<String, PathBuf>,
+    ) -> Result<Self> {
+        let format = ConfigFormat::from_path(path)?;
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        
+        let content = expand_env_vars(&content)?;
+        let mut config = Self::parse(&content, format)?;
         
-        let config: Config = serde_yaml::from_str(&content)
-            .with_context(|| "Failed to parse YAML configuration")?;
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if stack.contains(&canonical) {
+            bail!("Config include cycle: {} includes itself", path.display());
+        }
+        stack.push(canonical);
+        
+        // Duplicates within one file are left for validate() to report
+        for host in &config.hosts {
+            match host_sources.get(&host.name) {
+                Some(existing) if existing != path => bail!(
+                    "Host '{}' in {} is already defined in {}",
+                    host.name,
+                    path.display(),
+                    existing.display()
+                ),
+                _ => {
+                    host_sources.insert(host.name.clone(), path.to_path_buf());
+                }
+            }
+        }
+        
+        // Only hosts are taken from included files; the including file's settings win
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        for include in std::mem::take(&mut config.include) {
+            let include_path = base.join(&include);
+            let included = Self::load_with_includes(&include_path, stack, host_sources)
+                .with_context(|| format!("Failed to load included config: {}", include_path.display()))?;
+            config.hosts.extend(included.hosts);
+        }
+        
+        stack.pop();
+        Ok(config)
+    }
+
+    pub fn parse(content: &str, format: ConfigFormat) -> Result<Self> {
+        // Templates are expanded on a generic tree, but configs without any are
+        // parsed directly so errors keep their line numbers
+        let mut config: Config = match parse_tree(content, format) {
+            Some(mut tree) if uses_templates(&tree) => {
+                expand_templates(&mut tree)?;
+                serde_json::from_value(tree).with_context(|| "Failed to parse configuration")?
+            }
+            _ => match format {
+                ConfigFormat::Yaml => serde_yaml::from_str(content)
+                    .with_context(|| "Failed to parse YAML configuration")?,
+                ConfigFormat::Toml => toml::from_str(content)
+                    .with_context(|| "Failed to parse TOML configuration")?,
+                ConfigFormat::Json => serde_json::from_str(content)
+                    .with_context(|| "Failed to parse JSON configuration")?,
+            },
+        };
         
+        config.infer_default_ports()?;
         Ok(config)
     }
 
+    /// Fills in omitted ports from the protocol so every service has a concrete port.
+    fn infer_default_ports(&mut self) -> Result<()> {
+        let mut missing = Vec::new();
+
+        for host in &mut self.hosts {
+            for service in &mut host.services {
+                if service.port != 0 || !service.protocol.uses_port() {
+                    continue;
+                }
+                match service.protocol.default_port() {
+                    Some(port) => service.port = port,
+                    None => missing.push(format!(
+                        "host '{}', service '{}': port is required for {} services",
+                        host.name, service.name, service.protocol
+                    )),
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            bail!("Invalid configuration:\n  - {}", missing.join("\n  - "))
+        }
+    }
+
+    /// Seconds between checks for services on `host` without their own override.
+    pub fn host_refresh_interval(&self, host: &Host) -> u64 {
+        host.refresh_interval.unwrap_or(self.settings.refresh_interval)
+    }
+
+    /// Seconds between checks of `service`, falling back to the host and then the global interval.
+    pub fn service_refresh_interval(&self, host: &Host, service: &Service) -> u64 {
+        service
+            .refresh_interval
+            .unwrap_or_else(|| self.host_refresh_interval(host))
+    }
+
+    /// Whether the service or its host carries `tag`.
+    pub fn has_tag(host: &Host, service: &Service, tag: &str) -> bool {
+        host.tags.iter().chain(&service.tags).any(|t| t == tag)
+    }
+
+    /// Every tag used in the config, sorted.
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .hosts
+            .iter()
+            .flat_map(|host| host.tags.iter().chain(host.services.iter().flat_map(|s| &s.tags)))
+            .cloned()
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Drops services carrying none of `tags`, and hosts left with no services.
+    pub fn retain_tagged(&mut self, tags: &[String]) {
+        for host in &mut self.hosts {
+            let host_tags = host.tags.clone();
+            host.services
+                .retain(|service| host_tags.iter().chain(&service.tags).any(|t| tags.contains(t)));
+        }
+        self.hosts.retain(|host| !host.services.is_empty());
+    }
+
+    /// Shortest interval any service is checked at.
+    pub fn min_refresh_interval(&self) -> u64 {
+        self.hosts
+            .iter()
+            .flat_map(|host| host.services.iter().map(move |service| self.service_refresh_interval(host, service)))
+            .min()
+            .unwrap_or(self.settings.refresh_interval)
+    }
+
+    pub fn render(&self, format: ConfigFormat) -> Result<String> {
+        let content = match format {
+            ConfigFormat::Yaml => serde_yaml::to_string(self)
+                .with_context(|| "Failed to serialize configuration as YAML")?,
+            ConfigFormat::Toml => toml::to_string_pretty(self)
+                .with_context(|| "Failed to serialize configuration as TOML")?,
+            ConfigFormat::Json => serde_json::to_string_pretty(self)
+                .with_context(|| "Failed to serialize configuration as JSON")?,
+        };
+        
+        Ok(content)
+    }
+
+    /// Checks the whole config and reports every problem found at once.
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        let mut host_names = HashSet::new();
+
+        if self.settings.refresh_interval == 0 {
+            problems.push("settings.refresh_interval must be greater than 0".to_string());
+        }
+
+        if self.settings.ui_tick_ms == 0 {
+            problems.push("settings.ui_tick_ms must be greater than 0".to_string());
+        }
+
+        if let Some(bind_address) = &self.settings.bind_address {
+            match bind_address.parse::<IpAddr>() {
+                // Binding a throwaway socket shows whether the address is on this machine
+                Ok(ip) => {
+                    if let Err(e) = std::net::UdpSocket::bind((ip, 0)) {
+                        problems.push(format!("settings.bind_address {} is not available on this machine: {}", ip, e));
+                    }
+                }
+                Err(_) => problems.push(format!("settings.bind_address '{}' is not an IP address", bind_address)),
+            }
+        }
+
+        let mut peer_names = HashSet::new();
+        for peer in &self.settings.peers {
+            if peer.name.trim().is_empty() {
+                problems.push("settings.peers: every peer needs a name".to_string());
+            } else if !peer_names.insert(peer.name.as_str()) {
+                problems.push(format!("settings.peers: duplicate peer name '{}'", peer.name));
+            }
+            match reqwest::Url::parse(&peer.url) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                _ => problems.push(format!("settings.peers: '{}' is not an http or https URL", peer.url)),
+            }
+        }
+
+        if let Err(e) = self.settings.proxies() {
+            problems.push(format!("settings: {:#}", e));
+        }
+
+        if let Some(problem) = identity_problem(&self.settings.client_cert, &self.settings.client_key) {
+            problems.push(format!("settings: {}", problem));
+        }
+
+        for (host_index, host) in self.hosts.iter().enumerate() {
+            let host_label = if host.name.trim().is_empty() {
+                problems.push(format!("host #{} has an empty name", host_index + 1));
+                format!("host #{}", host_index + 1)
+            } else {
+                format!("host '{}'", host.name)
+            };
+
+            if !host.name.trim().is_empty() && !host_names.insert(host.name.as_str()) {
+                problems.push(format!("duplicate host name '{}'", host.name));
+            }
+
+            if let Some(problem) = address_problem(&host.address) {
+                problems.push(format!("{}: {}", host_label, problem));
+            }
+
+            if host.services.is_empty() {
+                problems.push(format!("{} has no services", host_label));
+            }
+
+            if host.refresh_interval == Some(0) {
+                problems.push(format!("{}: refresh_interval must be greater than 0", host_label));
+            }
+
+            let mut service_names = HashSet::new();
+            for (service_index, service) in host.services.iter().enumerate() {
+                let service_label = if service.name.trim().is_empty() {
+                    problems.push(format!("{}: service #{} has an empty name", host_label, service_index + 1));
+                    format!("{}, service #{}", host_label, service_index + 1)
+                } else {
+                    format!("{}, service '{}'", host_label, service.name)
+                };
+
+                if !service.name.trim().is_empty() && !service_names.insert(service.name.as_str()) {
+                    problems.push(format!("{}: duplicate service name '{}'", host_label, service.name));
+                }
+
+                if service.port == 0 && service.protocol.uses_port() {
+                    problems.push(format!("{}: port must be between 1 and 65535", service_label));
+                }
+
+                if matches!(service.protocol, Protocol::Unix) && !Path::new(&host.address).is_absolute() {
+                    problems.push(format!(
+                        "{}: unix services need the host address to be an absolute socket path, not '{}'",
+                        service_label, host.address
+                    ));
+                }
+
+                if matches!(service.protocol, Protocol::Dns)
+                    && service.query.as_deref().is_none_or(|q| q.trim().is_empty())
+                {
+                    problems.push(format!("{}: dns services require a query hostname", service_label));
+                }
+
+                if service.timeout == 0 {
+                    problems.push(format!("{}: timeout must be greater than 0", service_label));
+                }
+
+                if service.failure_threshold == 0 || service.success_threshold == 0 {
+                    problems.push(format!("{}: failure_threshold and success_threshold must be at least 1", service_label));
+                }
+
+                if service.slow_threshold == 0 {
+                    problems.push(format!("{}: slow_threshold must be at least 1", service_label));
+                }
+
+                if service.connect_timeout == Some(0) || service.read_timeout == Some(0) {
+                    problems.push(format!("{}: connect_timeout and read_timeout must be greater than 0", service_label));
+                }
+
+                for payload in [&service.send, &service.expect].into_iter().flatten() {
+                    if let Err(e) = unescape(payload) {
+                        problems.push(format!("{}: {:#}", service_label, e));
+                    }
+                }
+
+                if let Some(parent) = &service.depends_on {
+                    if parent == &service.name {
+                        problems.push(format!("{}: depends_on cannot name the service itself", service_label));
+                    } else if !host.services.iter().any(|other| &other.name == parent) {
+                        problems.push(format!("{}: depends_on names unknown service '{}'", service_label, parent));
+                    }
+                }
+
+                if let Some(problem) = identity_problem(&service.client_cert, &service.client_key) {
+                    problems.push(format!("{}: {}", service_label, problem));
+                }
+
+                for window in &service.maintenance {
+                    if let Err(e) = window.times() {
+                        problems.push(format!("{}: maintenance window {:#}", service_label, e));
+                    }
+                }
+
+                if service.refresh_interval == Some(0) {
+                    problems.push(format!("{}: refresh_interval must be greater than 0", service_label));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            bail!(
+                "Invalid configuration ({} problem{}):\n  - {}",
+                problems.len(),
+                if problems.len() == 1 { "" } else { "s" },
+                problems.join("\n  - ")
+            )
+        }
+    }
+
+    /// Settings that are valid but probably not what was meant, e.g. options
+    /// the service's protocol ignores.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if self.hosts.is_empty() {
+            warnings.push("no hosts are configured".to_string());
+        }
+
+        if self.settings.timezone.parse::<chrono_tz::Tz>().is_err() {
+            warnings.push(format!("unknown timezone '{}', UTC will be used", self.settings.timezone));
+        }
+
+        for host in &self.hosts {
+            if host.bypass_proxy && self.settings.http_proxy.is_none() && self.settings.https_proxy.is_none() {
+                warnings.push(format!("host '{}': bypass_proxy has no effect without http_proxy or https_proxy", host.name));
+            }
+            for service in &host.services {
+                let label = format!("host '{}', service '{}'", host.name, service.name);
+                let is_http = matches!(service.protocol, Protocol::Http | Protocol::Https);
+
+                if !is_http
+                    && (service.json_assert.is_some() || !service.headers.is_empty() || service.body.is_some() || !service.capture_headers.is_empty())
+                {
+                    warnings.push(format!("{}: HTTP request options are ignored for {} services", label, service.protocol));
+                }
+                if service.capture_headers.len() > MAX_CAPTURED_HEADERS {
+                    warnings.push(format!(
+                        "{}: only the first {} of capture_headers are recorded",
+                        label, MAX_CAPTURED_HEADERS
+                    ));
+                }
+                if service.http2 && !is_http {
+                    warnings.push(format!("{}: http2 is ignored for {} services", label, service.protocol));
+                }
+                if (service.client_cert.is_some() || service.client_key.is_some())
+                    && !matches!(service.protocol, Protocol::Https)
+                {
+                    warnings.push(format!("{}: client_cert is ignored for {} services", label, service.protocol));
+                }
+                if service.ws_ping && !matches!(service.protocol, Protocol::Ws | Protocol::Wss) {
+                    warnings.push(format!("{}: ws_ping is ignored for {} services", label, service.protocol));
+                }
+                if service.expected_banner.is_some() && !matches!(service.protocol, Protocol::Banner) {
+                    warnings.push(format!("{}: expected_banner is ignored for {} services", label, service.protocol));
+                }
+                if service.verify_open && !matches!(service.protocol, Protocol::Tcp) {
+                    warnings.push(format!("{}: verify_open is ignored for {} services", label, service.protocol));
+                } else if service.verify_open && (service.send.is_some() || service.expect.is_some() || service.keep_alive) {
+                    warnings.push(format!("{}: verify_open is ignored with send, expect or keep_alive", label));
+                }
+                if matches!(service.protocol, Protocol::Unix) && cfg!(not(unix)) {
+                    warnings.push(format!("{}: unix sockets aren't supported on this platform, it will show as unknown", label));
+                }
+                // A check that outlasts its interval delays the next one, so
+                // the service's result lags behind
+                let interval = self.service_refresh_interval(host, service);
+                let max_duration = service.max_check_duration();
+                if service.enabled && max_duration > Duration::from_secs(interval) {
+                    warnings.push(format!(
+                        "{}: a failing check can take {}s with its timeout and retries, longer than its {}s refresh interval; checks will run back to back and its status will lag",
+                        label,
+                        max_duration.as_secs_f64().ceil(),
+                        interval
+                    ));
+                }
+            }
+        }
+
+        warnings
+    }
+
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let content = serde_yaml::to_string(self)
-            .with_context(|| "Failed to serialize configuration")?;
+        let format = ConfigFormat::from_path(path.as_ref())?;
+        let content = self.render(format)?;
         
         fs::write(path.as_ref(), content)
             .with_context(|| format!("Failed to write config file: {}", path.as_ref().display()))?;
@@ -107,12 +1195,56 @@ impl Config {
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            hosts: vec![],
-            settings: Settings::default(),
+impl Settings {
+    /// Proxies from `http_proxy`/`https_proxy`, with `no_proxy` exempting
+    /// hosts. Empty when neither is set, leaving reqwest to use the
+    /// environment variables.
+    pub fn proxies(&self) -> Result<Vec<reqwest::Proxy>> {
+        let no_proxy = self.no_proxy.as_deref().and_then(reqwest::NoProxy::from_string);
+        let mut proxies = Vec::new();
+        if let Some(url) = &self.http_proxy {
+            let proxy = reqwest::Proxy::http(url).with_context(|| format!("invalid http_proxy '{}'", url))?;
+            proxies.push(proxy.no_proxy(no_proxy.clone()));
+        }
+        if let Some(url) = &self.https_proxy {
+            let proxy = reqwest::Proxy::https(url).with_context(|| format!("invalid https_proxy '{}'", url))?;
+            proxies.push(proxy.no_proxy(no_proxy));
+        }
+        Ok(proxies)
+    }
+
+    /// Whether `ip` falls in an IP or CIDR entry of `no_proxy`, for host
+    /// names that only resolve to an exempt address.
+    pub fn no_proxy_covers(&self, ip: IpAddr) -> bool {
+        self.no_proxy_networks().any(|(network, prefix)| in_network(ip, network, prefix))
+    }
+
+    /// The IP and CIDR entries of `no_proxy`; a bare IP is a full-length prefix.
+    pub fn no_proxy_networks(&self) -> impl Iterator<Item = (IpAddr, u8)> + '_ {
+        self.no_proxy.iter().flat_map(|list| list.split(',')).filter_map(|entry| {
+            let (ip, prefix) = match entry.trim().split_once('/') {
+                Some((ip, prefix)) => (ip.parse::<IpAddr>().ok()?, prefix.parse::<u8>().ok()?),
+                None => {
+                    let ip = entry.trim().parse::<IpAddr>().ok()?;
+                    (ip, if ip.is_ipv4() { 32 } else { 128 })
+                }
+            };
+            Some((ip, prefix))
+        })
+    }
+}
+
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix.min(32))).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix.min(128))).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
         }
+        _ => false,
     }
 }
 
@@ -123,6 +1255,41 @@ impl Default for Settings {
             log_file: None,
             theme: default_theme(),
             timezone: default_timezone(),
+            status_style: default_status_style(),
+            row_highlight: default_row_highlight(),
+            response_time_decimals: None,
+            thousands_separator: default_thousands_separator(),
+            view_mode: default_view_mode(),
+            host_order: default_host_order(),
+            availability_weighting: default_availability_weighting(),
+            max_concurrent_checks: default_max_concurrent_checks(),
+            check_jitter_ms: 0,
+            startup_ramp: false,
+            metrics_port: None,
+            api_port: None,
+            peers: Vec::new(),
+            notifications: false,
+            audible_alerts: false,
+            webhook_url: None,
+            alert_cooldown: 0,
+            cert_warn_days: default_cert_warn_days(),
+            user_agent: None,
+            accept_invalid_certs: false,
+            bind_address: None,
+            dns_cache_ttl: 0,
+            http_proxy: None,
+            https_proxy: None,
+            no_proxy: None,
+            max_response_bytes: default_max_response_bytes(),
+            client_cert: None,
+            client_key: None,
+            history_db: None,
+            state_file: None,
+            notes_file: None,
+            snapshot_dir: default_snapshot_dir(),
+            flap_threshold: default_flap_threshold(),
+            flap_window: default_flap_window(),
+            ui_tick_ms: default_ui_tick_ms(),
         }
     }
 } 
\ No newline at end of file
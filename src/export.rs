@@ -0,0 +1,110 @@
+use crate::monitor::{CheckSample, ServiceCheck, ServiceStatus};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+const CSV_HEADER: [&str; 9] = [
+    "host", "service", "address", "port", "protocol", "status", "response_ms", "last_check", "error",
+];
+
+pub fn format_statuses(statuses: &[ServiceCheck], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(statuses)
+            .with_context(|| "Failed to serialize statuses as JSON"),
+        ExportFormat::Csv => Ok(format_csv(statuses)),
+    }
+}
+
+/// Current statuses and each service's recent samples at one moment, as
+/// saved for a postmortem.
+#[derive(Serialize)]
+struct Snapshot<'a> {
+    taken_at: DateTime<Utc>,
+    statuses: &'a [ServiceCheck],
+    history: BTreeMap<&'a str, &'a VecDeque<CheckSample>>,
+}
+
+/// Writes `statuses` and `samples` as JSON to a new file in `dir`, named
+/// after the current time in `timezone`, and returns its path.
+pub fn write_snapshot(
+    dir: &Path,
+    timezone: &str,
+    statuses: &[ServiceCheck],
+    samples: &HashMap<String, VecDeque<CheckSample>>,
+) -> Result<PathBuf> {
+    let taken_at = Utc::now();
+    let stamp = match timezone.parse::<chrono_tz::Tz>() {
+        Ok(tz) => taken_at.with_timezone(&tz).format("%Y%m%d-%H%M%S-%3f"),
+        Err(_) => taken_at.format("%Y%m%d-%H%M%S-%3f"),
+    };
+    let path = dir.join(format!("daystrom-snapshot-{}.json", stamp));
+
+    let snapshot = Snapshot {
+        taken_at,
+        statuses,
+        history: samples.iter().map(|(key, recent)| (key.as_str(), recent)).collect(),
+    };
+    let json = serde_json::to_string_pretty(&snapshot).with_context(|| "Failed to serialize snapshot as JSON")?;
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create snapshot directory: {}", dir.display()))?;
+    std::fs::write(&path, json).with_context(|| format!("Failed to write snapshot file: {}", path.display()))?;
+    Ok(path)
+}
+
+/// Process exit code for a one-shot run: 1 if any service is down, otherwise
+/// 2 if any is unknown, otherwise 0. Degraded and maintenance count as up.
+pub fn exit_code(statuses: &[ServiceCheck]) -> i32 {
+    if statuses.iter().any(|check| check.status == ServiceStatus::Down) {
+        1
+    } else if statuses.iter().any(|check| matches!(check.status, ServiceStatus::Unknown | ServiceStatus::Pending)) {
+        2
+    } else {
+        0
+    }
+}
+
+fn format_csv(statuses: &[ServiceCheck]) -> String {
+    let mut lines = vec![csv_row(CSV_HEADER.iter().map(|s| s.to_string()))];
+
+    for status in statuses {
+        lines.push(csv_row([
+            status.host_name.clone(),
+            status.service_name.clone(),
+            status.address.clone(),
+            status.port.to_string(),
+            status.protocol.to_string(),
+            status.status.as_str().to_string(),
+            status.response_time.map(|t| t.as_millis().to_string()).unwrap_or_default(),
+            status.last_check.to_rfc3339(),
+            status.error_message.clone().unwrap_or_default(),
+        ]));
+    }
+
+    lines.join("\n")
+}
+
+fn csv_row(fields: impl IntoIterator<Item = String>) -> String {
+    fields
+        .into_iter()
+        .map(|field| csv_field(&field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Quotes a field when it contains a delimiter, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,88 @@
+use crate::monitor::ServiceCheck;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::mpsc::{self, Sender};
+use tracing::{error, info};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS checks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp TEXT NOT NULL,
+    host TEXT NOT NULL,
+    service TEXT NOT NULL,
+    status TEXT NOT NULL,
+    response_time_ms INTEGER NOT NULL,
+    error TEXT
+);
+CREATE INDEX IF NOT EXISTS checks_by_service ON checks (host, service, timestamp);
+";
+
+enum Message {
+    Record(Box<ServiceCheck>),
+    Flush(Sender<()>),
+}
+
+/// Appends check results to a SQLite database from a dedicated thread so
+/// database I/O never blocks the check loop.
+#[derive(Debug, Clone)]
+pub struct HistoryWriter {
+    sender: Sender<Message>,
+}
+
+impl HistoryWriter {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let connection = Connection::open(path)
+            .with_context(|| format!("Failed to open history database: {}", path.display()))?;
+        connection
+            .execute_batch(SCHEMA)
+            .with_context(|| "Failed to create history schema")?;
+        info!("Recording check history to {}", path.display());
+
+        let (sender, receiver) = mpsc::channel::<Message>();
+        std::thread::spawn(move || {
+            for message in receiver {
+                let check = match message {
+                    Message::Record(check) => check,
+                    Message::Flush(done) => {
+                        let _ = done.send(());
+                        continue;
+                    }
+                };
+                let result = connection.execute(
+                    "INSERT INTO checks (timestamp, host, service, status, response_time_ms, error)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        check.last_check.to_rfc3339(),
+                        check.host_name,
+                        check.service_name,
+                        check.status.as_str(),
+                        // The column predates unmeasured checks and is NOT NULL
+                        check.response_time.unwrap_or_default().as_millis() as i64,
+                        check.error_message,
+                    ],
+                );
+                if let Err(e) = result {
+                    error!("Failed to record check history: {}", e);
+                }
+            }
+        });
+
+        Ok(Self { sender })
+    }
+
+    pub fn record(&self, check: &ServiceCheck) {
+        if self.sender.send(Message::Record(Box::new(check.clone()))).is_err() {
+            error!("History writer has stopped; dropping check result");
+        }
+    }
+
+    /// Blocks until every result recorded so far has been written.
+    pub fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        if self.sender.send(Message::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+}
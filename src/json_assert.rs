@@ -0,0 +1,116 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A check against a JSON response body, written as `<path> == <value>`
+/// (e.g. `$.status == "ok"`) or just `<path>` to require the field exists.
+/// Parsed when the config is loaded, so a malformed one fails there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct JsonAssertion {
+    expression: String,
+    path: String,
+    segments: Vec<Segment>,
+    expected: Option<Value>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+impl JsonAssertion {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let (path, expected) = match expression.split_once("==") {
+            Some((path, expected)) => {
+                let expected = serde_json::from_str(expected.trim())
+                    .with_context(|| format!("expected value '{}' is not a JSON literal", expected.trim()))?;
+                (path.trim(), Some(expected))
+            }
+            None => (expression.trim(), None),
+        };
+
+        Ok(Self {
+            expression: expression.to_string(),
+            path: path.to_string(),
+            segments: parse_path(path)?,
+            expected,
+        })
+    }
+
+    /// Checks the assertion against a response body, describing any failure.
+    pub fn evaluate(&self, body: &str) -> std::result::Result<(), String> {
+        let document: Value =
+            serde_json::from_str(body).map_err(|e| format!("Response is not valid JSON: {}", e))?;
+
+        let mut current = &document;
+        for segment in &self.segments {
+            let next = match segment {
+                Segment::Key(key) => current.get(key),
+                Segment::Index(index) => current.get(index),
+            };
+            current = next.ok_or_else(|| format!("{} not found in response", self.path))?;
+        }
+
+        match &self.expected {
+            Some(expected) if current != expected => {
+                Err(format!("{} is {}, expected {}", self.path, current, expected))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl TryFrom<String> for JsonAssertion {
+    type Error = String;
+
+    fn try_from(expression: String) -> std::result::Result<Self, String> {
+        Self::parse(&expression).map_err(|e| format!("invalid json_assert '{}': {:#}", expression, e))
+    }
+}
+
+impl From<JsonAssertion> for String {
+    fn from(assertion: JsonAssertion) -> Self {
+        assertion.expression
+    }
+}
+
+/// Parses `$.a.b[0]["c d"]` into its segments.
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let Some(mut rest) = path.strip_prefix('$') else {
+        bail!("JSON path '{}' must start with '$'", path);
+    };
+
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                bail!("JSON path '{}' has an empty key", path);
+            }
+            segments.push(Segment::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after
+                .find(']')
+                .with_context(|| format!("JSON path '{}' has an unclosed '['", path))?;
+            let inner = after[..end].trim();
+            let segment = if let Some(key) = inner.strip_prefix('"').and_then(|k| k.strip_suffix('"')) {
+                Segment::Key(key.to_string())
+            } else {
+                Segment::Index(
+                    inner
+                        .parse()
+                        .with_context(|| format!("JSON path '{}' has an invalid index '{}'", path, inner))?,
+                )
+            };
+            segments.push(segment);
+            rest = &after[end + 1..];
+        } else {
+            bail!("JSON path '{}' is malformed near '{}'", path, rest);
+        }
+    }
+
+    Ok(segments)
+}
@@ -0,0 +1,365 @@
+use crate::app::App;
+use crossterm::event::KeyCode;
+
+/// Where a key binding applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    Anywhere,
+    OutsideHelp,
+    /// The services list or the triage view.
+    MainView,
+    /// The grouped services list only.
+    HostList,
+    /// Anywhere a row can be selected: the main views and host details.
+    Selection,
+    DetailView,
+    Graph,
+    Events,
+    FilterActive,
+    Help,
+}
+
+impl KeyContext {
+    /// Order of the sections in the help overlay.
+    pub const ALL: [KeyContext; 10] = [
+        KeyContext::Anywhere,
+        KeyContext::OutsideHelp,
+        KeyContext::MainView,
+        KeyContext::HostList,
+        KeyContext::Selection,
+        KeyContext::DetailView,
+        KeyContext::Graph,
+        KeyContext::Events,
+        KeyContext::FilterActive,
+        KeyContext::Help,
+    ];
+
+    pub fn applies(self, app: &App) -> bool {
+        match self {
+            KeyContext::Anywhere => true,
+            KeyContext::OutsideHelp => !app.show_help,
+            KeyContext::MainView => !app.show_help && !app.in_overlay() && !app.in_detail_view(),
+            KeyContext::HostList => !app.show_help && !app.in_overlay() && !app.in_detail_view() && !app.show_triage,
+            KeyContext::Selection => {
+                !app.show_help && !app.in_overlay() && (!app.in_detail_view() || app.in_host_detail_table())
+            }
+            KeyContext::DetailView => !app.in_overlay() && app.in_detail_view(),
+            KeyContext::Graph => !app.show_help && app.show_graph,
+            KeyContext::Events => !app.show_help && app.show_events,
+            KeyContext::FilterActive => app.is_filter_active(),
+            KeyContext::Help => app.show_help,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyContext::Anywhere => "Anywhere",
+            KeyContext::OutsideHelp => "Outside help",
+            KeyContext::MainView => "Services list and triage view",
+            KeyContext::HostList => "Services list",
+            KeyContext::Selection => "Services list, triage view and host details",
+            KeyContext::DetailView => "Detail views",
+            KeyContext::Graph => "Response time graph",
+            KeyContext::Events => "Status change log",
+            KeyContext::FilterActive => "While a name filter is active",
+            KeyContext::Help => "In this help",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    ToggleHelp,
+    ScrollHelpUp,
+    ScrollHelpDown,
+    ClearFilter,
+    NextItem,
+    PreviousItem,
+    StartFilter,
+    CycleSort,
+    ToggleTriage,
+    CycleTagFilter,
+    Refresh,
+    RefreshSelected,
+    TogglePause,
+    ToggleEnabled,
+    Acknowledge,
+    OpenUrl,
+    EnterDetail,
+    ToggleCollapse,
+    ExitDetail,
+    ScrollDetailUp,
+    ScrollDetailDown,
+    ToggleGraph,
+    ToggleEvents,
+    ScrollEventsUp,
+    ScrollEventsDown,
+    PageEventsUp,
+    PageEventsDown,
+    SaveSnapshot,
+    EditNote,
+    JumpToHost,
+}
+
+#[derive(Debug)]
+pub struct KeyBinding {
+    /// How the keys are shown in the help.
+    pub keys: &'static str,
+    pub codes: &'static [KeyCode],
+    pub description: &'static str,
+    pub context: KeyContext,
+    pub action: Action,
+}
+
+impl KeyBinding {
+    fn matches(&self, code: KeyCode) -> bool {
+        // Host jumps take whatever letter isn't bound to something else
+        self.codes.contains(&code)
+            || (self.action == Action::JumpToHost && matches!(code, KeyCode::Char(c) if c.is_alphanumeric()))
+    }
+}
+
+/// Every key binding, in priority order: when several match a key in the
+/// current context, the first one wins.
+pub const KEY_BINDINGS: &[KeyBinding] = &[
+    KeyBinding {
+        keys: "↑/k PgUp",
+        codes: &[KeyCode::Up, KeyCode::Char('k'), KeyCode::PageUp],
+        description: "Scroll up",
+        context: KeyContext::Help,
+        action: Action::ScrollHelpUp,
+    },
+    KeyBinding {
+        keys: "↓/j PgDn",
+        codes: &[KeyCode::Down, KeyCode::Char('j'), KeyCode::PageDown],
+        description: "Scroll down",
+        context: KeyContext::Help,
+        action: Action::ScrollHelpDown,
+    },
+    KeyBinding {
+        keys: "ESC",
+        codes: &[KeyCode::Esc],
+        description: "Clear the filter",
+        context: KeyContext::FilterActive,
+        action: Action::ClearFilter,
+    },
+    KeyBinding {
+        keys: "g/b/ESC",
+        codes: &[KeyCode::Char('g'), KeyCode::Char('b'), KeyCode::Char('B'), KeyCode::Esc],
+        description: "Close the graph",
+        context: KeyContext::Graph,
+        action: Action::ToggleGraph,
+    },
+    KeyBinding {
+        keys: "e/b/ESC",
+        codes: &[KeyCode::Char('e'), KeyCode::Char('b'), KeyCode::Char('B'), KeyCode::Esc],
+        description: "Close the log",
+        context: KeyContext::Events,
+        action: Action::ToggleEvents,
+    },
+    KeyBinding {
+        keys: "↑/k",
+        codes: &[KeyCode::Up, KeyCode::Char('k')],
+        description: "Scroll to newer changes",
+        context: KeyContext::Events,
+        action: Action::ScrollEventsUp,
+    },
+    KeyBinding {
+        keys: "↓/j",
+        codes: &[KeyCode::Down, KeyCode::Char('j')],
+        description: "Scroll to older changes",
+        context: KeyContext::Events,
+        action: Action::ScrollEventsDown,
+    },
+    KeyBinding {
+        keys: "PgUp",
+        codes: &[KeyCode::PageUp],
+        description: "Page to newer changes",
+        context: KeyContext::Events,
+        action: Action::PageEventsUp,
+    },
+    KeyBinding {
+        keys: "PgDn",
+        codes: &[KeyCode::PageDown],
+        description: "Page to older changes",
+        context: KeyContext::Events,
+        action: Action::PageEventsDown,
+    },
+    KeyBinding {
+        keys: "q/ESC",
+        codes: &[KeyCode::Char('q'), KeyCode::Esc],
+        description: "Quit",
+        context: KeyContext::Anywhere,
+        action: Action::Quit,
+    },
+    KeyBinding {
+        keys: "h",
+        codes: &[KeyCode::Char('h')],
+        description: "Toggle help",
+        context: KeyContext::Anywhere,
+        action: Action::ToggleHelp,
+    },
+    KeyBinding {
+        keys: "↑/k",
+        codes: &[KeyCode::Up, KeyCode::Char('k')],
+        description: "Previous item",
+        context: KeyContext::Anywhere,
+        action: Action::PreviousItem,
+    },
+    KeyBinding {
+        keys: "↓/j",
+        codes: &[KeyCode::Down, KeyCode::Char('j')],
+        description: "Next item",
+        context: KeyContext::Anywhere,
+        action: Action::NextItem,
+    },
+    KeyBinding {
+        keys: "/",
+        codes: &[KeyCode::Char('/')],
+        description: "Filter services by name",
+        context: KeyContext::Anywhere,
+        action: Action::StartFilter,
+    },
+    KeyBinding {
+        keys: "s",
+        codes: &[KeyCode::Char('s')],
+        description: "Cycle sort (name, status, response time)",
+        context: KeyContext::Anywhere,
+        action: Action::CycleSort,
+    },
+    KeyBinding {
+        keys: "r",
+        codes: &[KeyCode::Char('r')],
+        description: "Manual refresh",
+        context: KeyContext::Anywhere,
+        action: Action::Refresh,
+    },
+    KeyBinding {
+        keys: "p",
+        codes: &[KeyCode::Char('p')],
+        description: "Pause/resume monitoring",
+        context: KeyContext::Anywhere,
+        action: Action::TogglePause,
+    },
+    KeyBinding {
+        keys: "R",
+        codes: &[KeyCode::Char('R')],
+        description: "Re-check selected host or service",
+        context: KeyContext::OutsideHelp,
+        action: Action::RefreshSelected,
+    },
+    KeyBinding {
+        keys: "o",
+        codes: &[KeyCode::Char('o')],
+        description: "Open selected HTTP/HTTPS service in browser",
+        context: KeyContext::OutsideHelp,
+        action: Action::OpenUrl,
+    },
+    KeyBinding {
+        keys: "g",
+        codes: &[KeyCode::Char('g')],
+        description: "Graph response times of selected service, or of the slowest services",
+        context: KeyContext::OutsideHelp,
+        action: Action::ToggleGraph,
+    },
+    KeyBinding {
+        keys: "e",
+        codes: &[KeyCode::Char('e')],
+        description: "Show the log of status changes",
+        context: KeyContext::OutsideHelp,
+        action: Action::ToggleEvents,
+    },
+    KeyBinding {
+        keys: "n",
+        codes: &[KeyCode::Char('n')],
+        description: "Add, edit or clear a note on the selected service",
+        context: KeyContext::OutsideHelp,
+        action: Action::EditNote,
+    },
+    KeyBinding {
+        keys: "w",
+        codes: &[KeyCode::Char('w')],
+        description: "Save statuses and recent history to a snapshot file",
+        context: KeyContext::OutsideHelp,
+        action: Action::SaveSnapshot,
+    },
+    KeyBinding {
+        keys: "1",
+        codes: &[KeyCode::Char('1')],
+        description: "Toggle triage list of down and degraded services",
+        context: KeyContext::MainView,
+        action: Action::ToggleTriage,
+    },
+    KeyBinding {
+        keys: "t",
+        codes: &[KeyCode::Char('t')],
+        description: "Cycle tag filter",
+        context: KeyContext::MainView,
+        action: Action::CycleTagFilter,
+    },
+    KeyBinding {
+        keys: "a",
+        codes: &[KeyCode::Char('a')],
+        description: "Acknowledge selected failing service",
+        context: KeyContext::MainView,
+        action: Action::Acknowledge,
+    },
+    KeyBinding {
+        keys: "x",
+        codes: &[KeyCode::Char('x')],
+        description: "Disable/enable checks of selected service or host",
+        context: KeyContext::HostList,
+        action: Action::ToggleEnabled,
+    },
+    KeyBinding {
+        keys: "Space",
+        codes: &[KeyCode::Char(' ')],
+        description: "Collapse/expand host",
+        context: KeyContext::HostList,
+        action: Action::ToggleCollapse,
+    },
+    KeyBinding {
+        keys: "Enter",
+        codes: &[KeyCode::Enter],
+        description: "View host or service details",
+        context: KeyContext::Selection,
+        action: Action::EnterDetail,
+    },
+    KeyBinding {
+        keys: "b/B",
+        codes: &[KeyCode::Char('b'), KeyCode::Char('B')],
+        description: "Back to the previous view",
+        context: KeyContext::DetailView,
+        action: Action::ExitDetail,
+    },
+    KeyBinding {
+        keys: "PgUp",
+        codes: &[KeyCode::PageUp],
+        description: "Scroll error messages up",
+        context: KeyContext::DetailView,
+        action: Action::ScrollDetailUp,
+    },
+    KeyBinding {
+        keys: "PgDn",
+        codes: &[KeyCode::PageDown],
+        description: "Scroll error messages down",
+        context: KeyContext::DetailView,
+        action: Action::ScrollDetailDown,
+    },
+    KeyBinding {
+        keys: "Other letters",
+        codes: &[],
+        description: "Jump to next host starting with letter",
+        context: KeyContext::HostList,
+        action: Action::JumpToHost,
+    },
+];
+
+/// The action `code` triggers in the app's current state, if any.
+pub fn action_for(app: &App, code: KeyCode) -> Option<Action> {
+    KEY_BINDINGS
+        .iter()
+        .find(|binding| binding.matches(code) && binding.context.applies(app))
+        .map(|binding| binding.action)
+}
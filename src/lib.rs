@@ -1,6 +1,16 @@
+pub mod alerts;
+pub mod api;
 pub mod app;
 pub mod config;
+pub mod export;
+pub mod history;
+pub mod json_assert;
+pub mod keymap;
+pub mod metrics;
 pub mod monitor;
+pub mod peers;
+pub mod server;
+pub mod theme;
 pub mod ui;
 
 pub use app::App;
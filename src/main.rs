@@ -1,51 +1,157 @@
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use daystrom_tui::api;
 use daystrom_tui::app::App;
-use daystrom_tui::config::Config;
+use daystrom_tui::config::{Config, ConfigFormat};
+use daystrom_tui::export::{self, ExportFormat};
+use daystrom_tui::history::HistoryWriter;
+use daystrom_tui::metrics;
 use daystrom_tui::monitor::MonitorEngine;
 use daystrom_tui::ui::run_app;
-use std::path::PathBuf;
-use tracing::{error, info};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+// How often the state file is refreshed while running
+const STATE_SAVE_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Parser)]
 #[command(name = "daystrom-tui")]
 #[command(about = "A powerful TUI monitoring tool for multiple hosts and services")]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Configuration file path
-    #[arg(short, long, default_value = "config.yaml")]
+    #[arg(short, long, default_value = "config.yaml", global = true)]
     config: PathBuf,
 
     /// Log level
-    #[arg(short, long, default_value = "info")]
+    #[arg(short, long, default_value = "info", global = true)]
     log_level: String,
+
+    /// Only monitor services tagged with this tag (on the service or its host); repeat for several
+    #[arg(long = "filter-tag", value_name = "TAG")]
+    filter_tags: Vec<String>,
+
+    /// Print the config with includes merged and defaults filled in, then exit
+    #[arg(long, conflicts_with_all = ["export", "push_gateway"])]
+    print_config: bool,
+
+    /// Run one check cycle and export the results instead of launching the TUI
+    #[arg(long, value_enum)]
+    export: Option<ExportFormat>,
+
+    /// Write the export to this file instead of stdout
+    #[arg(short, long, requires = "export")]
+    output: Option<PathBuf>,
+
+    /// Run one check cycle and push the metrics to this Prometheus Pushgateway instead of launching the TUI
+    #[arg(long, value_name = "URL", conflicts_with = "export")]
+    push_gateway: Option<String>,
+
+    /// Job label for metrics pushed to the Pushgateway
+    #[arg(long, default_value = "daystrom", requires = "push_gateway")]
+    push_job: String,
+
+    /// Instance label for metrics pushed to the Pushgateway
+    #[arg(long, requires = "push_gateway")]
+    push_instance: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Load and validate the config, print a summary and exit
+    CheckConfig,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
+    if let Some(Command::CheckConfig) = cli.command {
+        init_logging(&cli.log_level, None);
+        return check_config(&cli.config);
+    }
 
-    info!("Starting Daystrom TUI monitoring application");
+    if cli.print_config {
+        init_logging(&cli.log_level, None);
+        return print_config(&cli.config);
+    }
+
+    // Load configuration first, since it may send logs to a file
+    let mut config = Config::load_from_file(&cli.config)?;
+    init_logging(&cli.log_level, config.settings.log_file.as_deref());
 
-    // Load configuration
-    let config = Config::load_from_file(&cli.config)?;
+    info!("Starting Daystrom TUI monitoring application");
+    config.validate()?;
     info!("Loaded configuration from {}", cli.config.display());
+    if !cli.filter_tags.is_empty() {
+        config.retain_tagged(&cli.filter_tags);
+        if config.hosts.is_empty() {
+            bail!("No services are tagged with {}", cli.filter_tags.join(", "));
+        }
+        info!("Filtered to services tagged {}", cli.filter_tags.join(", "));
+    }
     info!("Monitoring {} hosts with {} total services", 
           config.hosts.len(), 
           config.hosts.iter().map(|h| h.services.len()).sum::<usize>());
 
     // Create monitoring engine
-    let engine = MonitorEngine::new(config.clone());
+    let mut engine = MonitorEngine::new(config.clone());
+    if let Some(path) = &config.settings.history_db {
+        engine = engine.with_history(HistoryWriter::open(path)?);
+    }
+
+    if let Some(format) = cli.export {
+        let statuses = engine.check_once().await;
+        let output = export::format_statuses(&statuses, format)?;
+        engine.flush_history();
+        match &cli.output {
+            Some(path) => {
+                std::fs::write(path, output)
+                    .with_context(|| format!("Failed to write export file: {}", path.display()))?;
+                info!("Exported statuses to {}", path.display());
+            }
+            None => println!("{}", output),
+        }
+        std::process::exit(export::exit_code(&statuses));
+    }
+
+    if let Some(gateway) = &cli.push_gateway {
+        let statuses = engine.check_once().await;
+        engine.flush_history();
+        metrics::push(gateway, &cli.push_job, cli.push_instance.as_deref(), &statuses).await?;
+        info!("Pushed metrics for {} services to {}", statuses.len(), gateway);
+        std::process::exit(export::exit_code(&statuses));
+    }
     
     // Start monitoring in background
+    let state_file = config.settings.state_file.as_ref().map(PathBuf::from);
+    if let Some(path) = state_file.as_deref().filter(|path| path.exists()) {
+        match engine.restore_state(path).await {
+            Ok(count) => info!("Restored {} last-known statuses from {}", count, path.display()),
+            Err(e) => warn!("Ignoring state file: {:#}", e),
+        }
+    }
+
     let engine_handle = engine.start().await;
+    let state_handle = state_file
+        .clone()
+        .map(|path| engine.spawn_state_saver(path, STATE_SAVE_INTERVAL));
+
+    let metrics_handle = match config.settings.metrics_port {
+        Some(port) => Some(metrics::serve(port, engine.clone()).await?),
+        None => None,
+    };
+
+    let api_handle = match config.settings.api_port {
+        Some(port) => Some(api::serve(port, engine.clone()).await?),
+        None => None,
+    };
 
     // Create and run TUI app
-    let app = App::new(config, engine);
+    let app = App::new(config, engine.clone());
     
     if let Err(e) = run_app(app).await {
         error!("Application error: {}", e);
@@ -54,7 +160,88 @@ async fn main() -> Result<()> {
 
     // Stop monitoring engine
     engine_handle.abort();
+    engine.flush_history();
+    if let Some(handle) = state_handle {
+        handle.abort();
+    }
+    if let Some(path) = &state_file {
+        if let Err(e) = engine.save_state(path).await {
+            warn!("Failed to save state: {:#}", e);
+        }
+    }
+    if let Some(handle) = metrics_handle {
+        handle.abort();
+    }
+    if let Some(handle) = api_handle {
+        handle.abort();
+    }
     
     info!("Application shutdown complete");
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Logs to `log_file` when given, otherwise to stderr. `RUST_LOG` takes
+/// precedence over `--log-level`.
+fn init_logging(level: &str, log_file: Option<&str>) {
+    let filter = || {
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(level))
+    };
+
+    let file = log_file.map(|path| {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| (path, e))
+    });
+
+    match file {
+        Some(Ok(file)) => {
+            tracing_subscriber::fmt()
+                .with_env_filter(filter())
+                .with_writer(std::sync::Mutex::new(file))
+                .with_ansi(false)
+                .init();
+        }
+        Some(Err((path, e))) => {
+            tracing_subscriber::fmt()
+                .with_env_filter(filter())
+                .with_writer(std::io::stderr)
+                .init();
+            warn!("Could not open log file {}, logging to stderr: {}", path, e);
+        }
+        None => {
+            tracing_subscriber::fmt()
+                .with_env_filter(filter())
+                .with_writer(std::io::stderr)
+                .init();
+        }
+    }
+}
+
+/// Validates the config without starting any checks, for use in CI.
+fn check_config(path: &Path) -> Result<()> {
+    let config = Config::load_from_file(path)?;
+    config.validate()?;
+
+    let warnings = config.warnings();
+    println!("{}: OK", path.display());
+    println!("  Hosts: {}", config.hosts.len());
+    println!("  Services: {}", config.hosts.iter().map(|h| h.services.len()).sum::<usize>());
+    println!("  Warnings: {}", warnings.len());
+    for warning in &warnings {
+        println!("  - {}", warning);
+    }
+
+    Ok(())
+}
+
+/// Prints the config as it is actually used, in the same format as the file.
+fn print_config(path: &Path) -> Result<()> {
+    let mut config = Config::load_from_file(path)?;
+    // Included hosts are already merged in
+    config.include.clear();
+    print!("{}", config.render(ConfigFormat::from_path(path)?)?);
+    Ok(())
+}
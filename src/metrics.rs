@@ -0,0 +1,106 @@
+use crate::monitor::{MonitorEngine, ServiceCheck, ServiceStatus};
+use crate::server::{self, Response};
+use anyhow::{bail, Context, Result};
+use std::time::Duration;
+use std::fmt::Write;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Renders service statuses in the Prometheus text exposition format.
+pub fn render_metrics(statuses: &[ServiceCheck]) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP daystrom_service_up Whether the service check succeeded (1) or not (0).\n");
+    out.push_str("# TYPE daystrom_service_up gauge\n");
+    for check in statuses {
+        // Degraded services still answer, and planned maintenance shouldn't
+        // page anyone, so both count as up here
+        let up = matches!(
+            check.status,
+            ServiceStatus::Up | ServiceStatus::Degraded | ServiceStatus::Maintenance
+        ) as u8;
+        let _ = writeln!(out, "daystrom_service_up{{{}}} {}", labels(check), up);
+    }
+
+    out.push_str("# HELP daystrom_response_time_ms Duration of the last check in milliseconds.\n");
+    out.push_str("# TYPE daystrom_response_time_ms gauge\n");
+    // Checks that never probed (maintenance, dependency skips) have no sample
+    for check in statuses {
+        if let Some(response_time) = check.response_time {
+            let _ = writeln!(
+                out,
+                "daystrom_response_time_ms{{{}}} {}",
+                labels(check),
+                response_time.as_millis()
+            );
+        }
+    }
+
+    out
+}
+
+fn labels(check: &ServiceCheck) -> String {
+    format!(
+        "host=\"{}\",service=\"{}\",protocol=\"{}\"",
+        escape_label(&check.host_name),
+        escape_label(&check.service_name),
+        check.protocol
+    )
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Starts the `/metrics` scrape endpoint on the given port.
+pub async fn serve(port: u16, engine: MonitorEngine) -> Result<tokio::task::JoinHandle<()>> {
+    server::serve("metrics", port, move |path| {
+        let engine = engine.clone();
+        async move {
+            match path.as_str() {
+                "/metrics" => {
+                    let statuses = engine.get_sorted_statuses().await;
+                    Response::ok(CONTENT_TYPE, render_metrics(&statuses))
+                }
+                _ => Response::not_found(),
+            }
+        }
+    })
+    .await
+}
+
+/// Pushes the gauges for `statuses` to a Prometheus Pushgateway, replacing
+/// any metrics previously pushed under the same job and instance.
+pub async fn push(gateway: &str, job: &str, instance: Option<&str>, statuses: &[ServiceCheck]) -> Result<()> {
+    let mut url = reqwest::Url::parse(gateway)
+        .with_context(|| format!("Invalid Pushgateway URL: {}", gateway))?;
+    {
+        let mut segments = url
+            .path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid Pushgateway URL: {}", gateway))?;
+        segments.pop_if_empty().extend(["metrics", "job", job]);
+        if let Some(instance) = instance {
+            segments.extend(["instance", instance]);
+        }
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let response = client
+        .put(url.clone())
+        .header(reqwest::header::CONTENT_TYPE, CONTENT_TYPE)
+        .body(render_metrics(statuses))
+        .send()
+        .await
+        .with_context(|| format!("Failed to push metrics to {}", url))?;
+
+    if !response.status().is_success() {
+        bail!("Pushgateway at {} answered {}", url, response.status());
+    }
+
+    Ok(())
+}
@@ -1,112 +1,745 @@
-use crate::config::{Config, Host, Protocol, Service};
+use crate::alerts::{self, Transition};
+use crate::config::{load_identity, unescape, Config, Host, HttpMethod, Protocol, Service, Settings, MAX_CAPTURED_HEADERS};
+use crate::history::HistoryWriter;
+use crate::peers::{self, PeerSnapshot};
 
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
 use reqwest::Client;
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpSocket, TcpStream};
+use tokio::sync::{RwLock, Semaphore};
 use tokio::time::{Duration, Instant};
-use tracing::{debug, error, info};
+use tokio_tungstenite::tungstenite::Message;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+use tracing::{debug, error, info, warn};
+use trust_dns_resolver::config::{NameServerConfig, Protocol as DnsProtocol, ResolverConfig, ResolverOpts};
+use trust_dns_resolver::error::ResolveErrorKind;
+use trust_dns_resolver::TokioAsyncResolver;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ServiceStatus {
     Up,
+    Degraded,
     Down,
     Unknown,
+    /// Not checked yet this run.
+    Pending,
+    /// Inside a scheduled maintenance window; not probed.
+    Maintenance,
+}
+
+impl ServiceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceStatus::Up => "up",
+            ServiceStatus::Degraded => "degraded",
+            ServiceStatus::Down => "down",
+            ServiceStatus::Unknown => "unknown",
+            ServiceStatus::Pending => "pending",
+            ServiceStatus::Maintenance => "maintenance",
+        }
+    }
+
+    /// Higher values are worse; used to order services for triage.
+    pub fn severity(&self) -> u8 {
+        match self {
+            ServiceStatus::Up => 0,
+            ServiceStatus::Maintenance => 1,
+            ServiceStatus::Pending => 2,
+            ServiceStatus::Unknown => 3,
+            ServiceStatus::Degraded => 4,
+            ServiceStatus::Down => 5,
+        }
+    }
 }
 
 impl std::fmt::Display for ServiceStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ServiceStatus::Up => write!(f, "🟢 UP"),
+            ServiceStatus::Degraded => write!(f, "🟠 DEGRADED"),
             ServiceStatus::Down => write!(f, "🔴 DOWN"),
             ServiceStatus::Unknown => write!(f, "🟡 UNKNOWN"),
+            ServiceStatus::Pending => write!(f, "⏳ PENDING"),
+            ServiceStatus::Maintenance => write!(f, "🔧 MAINTENANCE"),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceCheck {
+    pub key: String,
     pub host_name: String,
     pub service_name: String,
     pub address: String,
     pub port: u16,
     pub protocol: Protocol,
+    /// Effective status, after `failure_threshold`/`success_threshold`.
     pub status: ServiceStatus,
+    /// What the latest check alone found.
+    pub raw_status: ServiceStatus,
+    /// When `status` last changed; carried over while it stays the same.
+    #[serde(default = "Utc::now")]
+    pub status_since: DateTime<Utc>,
     pub last_check: DateTime<Utc>,
-    pub response_time: Duration,
+    /// `None` until a probe has actually run, e.g. for maintenance or
+    /// dependency skips.
+    #[serde(
+        rename = "response_time_ms",
+        serialize_with = "serialize_optional_duration_ms",
+        deserialize_with = "deserialize_optional_duration_ms"
+    )]
+    pub response_time: Option<Duration>,
+    /// HTTP version the server answered an `http`/`https` check with.
+    #[serde(default)]
+    pub http_version: Option<String>,
+    /// Values of the service's `capture_headers` in the last response.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<(String, String)>,
     pub error_message: Option<String>,
+    pub detail: Option<String>,
+    pub cert_expiry: Option<DateTime<Utc>>,
+    /// Changing state too often to trust; transition alerts are suppressed.
+    pub flapping: bool,
+    /// Checks were switched off at runtime; this is the last result before that.
+    #[serde(default)]
+    pub disabled: bool,
+    /// Loaded from the state file at startup rather than checked this run.
+    #[serde(skip)]
+    pub restored: bool,
+    /// Peer the result was pulled from; `None` for this instance's own checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vantage: Option<String>,
+}
+
+/// One past result for a service, kept in memory for trends.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckSample {
+    /// When the check finished; places the sample on the response time graph.
+    pub timestamp: DateTime<Utc>,
+    pub status: ServiceStatus,
+    #[serde(rename = "response_time_ms", serialize_with = "serialize_optional_duration_ms")]
+    pub response_time: Option<Duration>,
+}
+
+// Recent results kept per service
+const RECENT_SAMPLES: usize = 100;
+
+// Idle time before keepalive probes a pooled connection, and between probes
+const POOLED_KEEPALIVE_IDLE: Duration = Duration::from_secs(10);
+
+// Status changes kept for the events panel
+const EVENT_LOG_SIZE: usize = 200;
+
+// How long a pull from a peer's status API may take
+const PEER_TIMEOUT: Duration = Duration::from_secs(10);
+
+// How long a `verify_open` connection must stay up after connecting
+const VERIFY_OPEN_WAIT: Duration = Duration::from_millis(250);
+
+// Most bytes a payload check reads while looking for `expect`
+const MAX_PAYLOAD_RESPONSE: usize = 4096;
+
+/// Run of consecutive failed or successful checks for one service.
+#[derive(Debug, Clone, Copy)]
+struct Streak {
+    failing: bool,
+    count: u32,
+}
+
+/// How long checks are taking, to tell when the engine starts falling behind.
+#[derive(Debug, Clone, Default)]
+pub struct CheckTiming {
+    /// How long the last round of checks took, from the earliest start to the
+    /// last finish, once every enabled service has been checked.
+    pub last_cycle: Option<Duration>,
+    /// Slowest latest check: its key, how long it took with retries, and its
+    /// refresh interval.
+    pub slowest: Option<(String, Duration, Duration)>,
+}
+
+/// Raw data behind `CheckTiming`.
+#[derive(Debug, Default)]
+struct Timings {
+    last_cycle: Option<Duration>,
+    /// When each service's running check started.
+    started: HashMap<String, Instant>,
+    /// When the latest check of each service finished so far in the current
+    /// round started.
+    round: HashMap<String, Instant>,
+    /// Latest check duration and refresh interval per service key.
+    checks: HashMap<String, (Duration, Duration)>,
+}
+
+impl Timings {
+    fn start_check(&mut self, key: &str) {
+        self.started.insert(key.to_string(), Instant::now());
+    }
+
+    /// Counts a finished check towards the current round. Once all `enabled`
+    /// services have finished one, the round closes and the cycle time is
+    /// how long ago the earliest of their latest checks started.
+    fn record_round(&mut self, key: &str, enabled: usize) {
+        let started = self.started.remove(key).unwrap_or_else(Instant::now);
+        self.round.insert(key.to_string(), started);
+        if self.round.len() >= enabled {
+            self.last_cycle = self.round.values().min().map(|earliest| earliest.elapsed());
+            self.round.clear();
+        }
+    }
+
+    /// Drops a service whose checks were switched off.
+    fn forget(&mut self, key: &str) {
+        self.checks.remove(key);
+        self.started.remove(key);
+        self.round.remove(key);
+    }
+}
+
+/// What an HTTP check needs from the client it is sent with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientOptions {
+    follow_redirects: bool,
+    connect_timeout: Duration,
+    keep_alive: bool,
+    http2: bool,
+    identity: Option<(String, String)>,
+    /// Bypass the configured proxies.
+    direct: bool,
+}
+
+impl ClientOptions {
+    fn for_service(service: &Service, settings: &Settings, direct: bool) -> Self {
+        Self {
+            follow_redirects: service.follow_redirects,
+            connect_timeout: service.connect_timeout(),
+            keep_alive: service.keep_alive,
+            http2: service.http2,
+            identity: service
+                .client_identity(settings)
+                .map(|(cert, key)| (cert.to_string(), key.to_string())),
+            direct,
+        }
+    }
+}
+
+/// Outcome of a single probe, before retries are accounted for.
+#[derive(Debug, Clone)]
+struct ProbeResult {
+    status: ServiceStatus,
+    error: Option<String>,
+    detail: Option<String>,
+    cert_expiry: Option<DateTime<Utc>>,
+    http_version: Option<String>,
+    headers: Vec<(String, String)>,
+    /// Answered from an already open connection, with no round-trip to time.
+    reused_connection: bool,
+}
+
+impl From<(ServiceStatus, Option<String>)> for ProbeResult {
+    fn from((status, error): (ServiceStatus, Option<String>)) -> Self {
+        Self {
+            status,
+            error,
+            detail: None,
+            cert_expiry: None,
+            http_version: None,
+            headers: Vec::new(),
+            reused_connection: false,
+        }
+    }
+}
+
+fn serialize_duration_ms<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
+fn serialize_optional_duration_ms<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serialize_duration_ms(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_optional_duration_ms<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
 }
 
 impl ServiceCheck {
-    pub fn new(host: &Host, service: &Service) -> Self {
+    /// Builds the status-map key for a service. The config indices keep it
+    /// unique even when two services share a name, protocol and port.
+    pub fn key_for(host_index: usize, service_index: usize, host: &Host, service: &Service) -> String {
+        format!(
+            "{}.{}:{}:{}:{}:{}",
+            host_index, service_index, host.name, service.name, service.protocol, service.port
+        )
+    }
+
+    /// Recovers the config indices encoded in the key by `key_for`.
+    pub fn config_indices(&self) -> Option<(usize, usize)> {
+        let (indices, _) = self.key.split_once(':')?;
+        let (host_index, service_index) = indices.split_once('.')?;
+        Some((host_index.parse().ok()?, service_index.parse().ok()?))
+    }
+
+    pub fn new(key: String, host: &Host, service: &Service) -> Self {
         Self {
+            key,
             host_name: host.name.clone(),
             service_name: service.name.clone(),
             address: host.address.clone(),
             port: service.port,
             protocol: service.protocol.clone(),
             status: ServiceStatus::Unknown,
+            raw_status: ServiceStatus::Unknown,
+            status_since: Utc::now(),
             last_check: Utc::now(),
-            response_time: Duration::from_secs(0),
+            response_time: None,
+            http_version: None,
+            headers: Vec::new(),
             error_message: None,
+            detail: None,
+            cert_expiry: None,
+            flapping: false,
+            disabled: false,
+            restored: false,
+            vantage: None,
         }
     }
 }
 
+/// One consistent generation of results, keyed by status key. Snapshots are
+/// never modified once published; writers swap in a new one.
+pub type StatusSnapshot = Arc<HashMap<String, ServiceCheck>>;
+
+/// Clears `cycle_running` once a cycle ends, even if it is cancelled.
+struct CycleGuard<'a>(&'a AtomicBool);
+
+impl Drop for CycleGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Results of a check cycle, held back until every check in it finishes.
+type CycleResults = Arc<std::sync::Mutex<Vec<ServiceCheck>>>;
+
 #[derive(Debug)]
 pub struct MonitorEngine {
-    config: Config,
-    statuses: Arc<RwLock<HashMap<String, ServiceCheck>>>,
+    /// Shared by every clone, so spawned checks borrow hosts and services
+    /// from it instead of copying them.
+    config: Arc<Config>,
+    statuses: Arc<RwLock<StatusSnapshot>>,
+    samples: Arc<RwLock<HashMap<String, VecDeque<CheckSample>>>>,
     http_client: Client,
+    /// Pulls `settings.peers`, always verifying their certificates.
+    peer_client: Client,
+    /// reqwest fixes the redirect policy, connect timeout, pooling and client
+    /// certificate per client, so checks use one client for each combination
+    /// the config asks for.
+    check_clients: Arc<HashMap<ClientOptions, Client>>,
+    /// Open connections for keep-alive TCP checks, keyed by `address:port`.
+    connections: Arc<tokio::sync::Mutex<HashMap<String, TcpStream>>>,
+    /// Host names resolved for checks and when, for `settings.dns_cache_ttl`.
+    resolved: Arc<tokio::sync::Mutex<HashMap<String, (IpAddr, Instant)>>>,
+    /// Local address checks connect from, from `settings.bind_address`.
+    bind_address: Option<IpAddr>,
+    /// Consecutive failures or successes per service key.
+    streaks: Arc<tokio::sync::Mutex<HashMap<String, Streak>>>,
+    /// Consecutive checks slower than `warn_response_ms` per service key.
+    slow_streaks: Arc<tokio::sync::Mutex<HashMap<String, u32>>>,
+    /// When each service last sent an alert, for `alert_cooldown`.
+    last_alerts: Arc<tokio::sync::Mutex<HashMap<String, Instant>>>,
+    /// The status last alerted for services whose changes are being held
+    /// back while they flap.
+    flap_held: Arc<tokio::sync::Mutex<HashMap<String, ServiceStatus>>>,
+    check_limit: Arc<Semaphore>,
+    paused: Arc<AtomicBool>,
+    /// Set while `check_all_services` runs, so cycles can't pile up.
+    cycle_running: Arc<AtomicBool>,
+    /// Bumped whenever a result is stored, so readers can skip unchanged maps.
+    version: Arc<AtomicU64>,
+    /// When a result was last stored; starts at engine creation.
+    last_result_at: Arc<std::sync::Mutex<DateTime<Utc>>>,
+    history: Option<HistoryWriter>,
+    /// Results pulled from `settings.peers`, replaced as a whole like `statuses`.
+    peer_results: Arc<RwLock<PeerSnapshot>>,
+    timings: Arc<std::sync::Mutex<Timings>>,
+    /// The latest `EVENT_LOG_SIZE` status changes, oldest first.
+    transitions: Arc<std::sync::Mutex<VecDeque<Transition>>>,
 }
 
 impl MonitorEngine {
     pub fn new(config: Config) -> Self {
-        let http_client = Client::builder()
+        let user_agent = config
+            .settings
+            .user_agent
+            .clone()
+            .unwrap_or_else(|| format!("daystrom-tui/{}", env!("CARGO_PKG_VERSION")));
+        let accept_invalid_certs = config.settings.accept_invalid_certs;
+        // Already checked by `Config::validate`
+        let bind_address: Option<IpAddr> = config.settings.bind_address.as_deref().and_then(|a| a.parse().ok());
+        if accept_invalid_certs {
+            warn!("TLS certificate verification is disabled (settings.accept_invalid_certs)");
+        }
+        let proxies = config.settings.proxies().unwrap_or_else(|e| {
+            error!("{:#}", e);
+            Vec::new()
+        });
+        
+        // Used for webhooks, which always verify certificates
+        let mut http_client = Client::builder()
             .timeout(Duration::from_secs(30))
+            .user_agent(user_agent.clone())
+            .local_address(bind_address);
+        for proxy in &proxies {
+            http_client = http_client.proxy(proxy.clone());
+        }
+        let http_client = http_client
+            .build()
+            .expect("Failed to create HTTP client");
+        
+        let mut peer_client = Client::builder()
+            .timeout(PEER_TIMEOUT)
+            .user_agent(user_agent.clone())
+            .local_address(bind_address);
+        for proxy in &proxies {
+            peer_client = peer_client.proxy(proxy.clone());
+        }
+        let peer_client = peer_client
             .build()
             .expect("Failed to create HTTP client");
+        
+        // Hosts can opt out of the proxies, so each option set also gets a direct client
+        let routes: &[bool] = if proxies.is_empty() { &[false] } else { &[false, true] };
+        let mut check_clients = HashMap::new();
+        for service in config.hosts.iter().flat_map(|host| &host.services) {
+            for &direct in routes {
+                let options = ClientOptions::for_service(service, &config.settings, direct);
+                if check_clients.contains_key(&options) {
+                    continue;
+                }
+                let redirect = if options.follow_redirects {
+                    reqwest::redirect::Policy::default()
+                } else {
+                    reqwest::redirect::Policy::none()
+                };
+                let mut builder = Client::builder()
+                    .connect_timeout(options.connect_timeout)
+                    .redirect(redirect)
+                    .user_agent(user_agent.clone())
+                    .danger_accept_invalid_certs(accept_invalid_certs)
+                    .local_address(bind_address);
+                if options.direct {
+                    builder = builder.no_proxy();
+                } else {
+                    for proxy in &proxies {
+                        builder = builder.proxy(proxy.clone());
+                    }
+                }
+                // Idle connections would otherwise close between slow checks
+                if options.keep_alive {
+                    builder = builder.pool_idle_timeout(None);
+                }
+                // Skip negotiation for servers that only speak HTTP/2
+                if options.http2 {
+                    builder = builder.http2_prior_knowledge();
+                }
+                if let Some((cert, key)) = &options.identity {
+                    // Validation loads these too; only a file changed since then fails here
+                    match load_identity(cert, key) {
+                        Ok(identity) => builder = builder.identity(identity),
+                        Err(e) => error!("{:#}", e),
+                    }
+                }
+                let client = builder.build().expect("Failed to create HTTP client");
+                check_clients.insert(options, client);
+            }
+        }
+
+        // A limit of zero would block every check forever
+        let max_concurrent = config.settings.max_concurrent_checks.max(1);
+        
+        // Every service shows as pending until its first check finishes
+        let mut pending = HashMap::new();
+        for (host_index, host) in config.hosts.iter().enumerate() {
+            for (service_index, service) in host.services.iter().enumerate().filter(|(_, service)| service.enabled) {
+                let key = ServiceCheck::key_for(host_index, service_index, host, service);
+                let mut check = ServiceCheck::new(key.clone(), host, service);
+                check.status = ServiceStatus::Pending;
+                check.raw_status = ServiceStatus::Pending;
+                pending.insert(key, check);
+            }
+        }
 
         Self {
-            config,
-            statuses: Arc::new(RwLock::new(HashMap::new())),
+            config: Arc::new(config),
+            statuses: Arc::new(RwLock::new(Arc::new(pending))),
+            samples: Arc::new(RwLock::new(HashMap::new())),
             http_client,
+            peer_client,
+            check_clients: Arc::new(check_clients),
+            connections: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            resolved: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            bind_address,
+            streaks: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            slow_streaks: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            last_alerts: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            flap_held: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            check_limit: Arc::new(Semaphore::new(max_concurrent)),
+            paused: Arc::new(AtomicBool::new(false)),
+            cycle_running: Arc::new(AtomicBool::new(false)),
+            version: Arc::new(AtomicU64::new(0)),
+            last_result_at: Arc::new(std::sync::Mutex::new(Utc::now())),
+            history: None,
+            peer_results: Arc::new(RwLock::new(PeerSnapshot::default())),
+            timings: Arc::new(std::sync::Mutex::new(Timings::default())),
+            transitions: Arc::new(std::sync::Mutex::new(VecDeque::new())),
+        }
+    }
+
+    pub fn with_history(mut self, history: HistoryWriter) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Waits for pending history writes; call before exiting.
+    pub fn flush_history(&self) {
+        if let Some(history) = &self.history {
+            history.flush();
         }
     }
 
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    pub fn last_result_at(&self) -> DateTime<Utc> {
+        *self.last_result_at.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     pub async fn start(&self) -> tokio::task::JoinHandle<()> {
-        let interval = Duration::from_secs(self.config.settings.refresh_interval);
         let engine = self.clone();
         
         tokio::spawn(async move {
-            info!("Starting monitoring engine with {} second interval", interval.as_secs());
+            info!(
+                "Starting monitoring engine with {} second default interval",
+                engine.config.settings.refresh_interval
+            );
             
-            // Initial check
-            engine.check_all_services().await;
+            // Each service runs on its own timer; dropping the set aborts them all
+            let mut timers = tokio::task::JoinSet::new();
             
-            let mut interval_timer = tokio::time::interval(interval);
+            let enabled: Vec<(usize, usize)> = engine
+                .config
+                .hosts
+                .iter()
+                .enumerate()
+                .flat_map(|(host_index, host)| {
+                    host.services
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, service)| service.enabled)
+                        .map(move |(service_index, _)| (host_index, service_index))
+                })
+                .collect();
             
-            loop {
-                interval_timer.tick().await;
-                engine.check_all_services().await;
+            for (position, &(host_index, service_index)) in enabled.iter().enumerate() {
+                let host = &engine.config.hosts[host_index];
+                let interval = Duration::from_secs(engine.config.service_refresh_interval(host, &host.services[service_index]));
+                // With startup_ramp, service n of N starts n/N of the way into its interval
+                let ramp = if engine.config.settings.startup_ramp {
+                    interval.mul_f64(position as f64 / enabled.len() as f64)
+                } else {
+                    Duration::ZERO
+                };
+                let engine = engine.clone();
+                
+                timers.spawn(async move {
+                    engine.run_service_timer(host_index, service_index, interval, ramp).await;
+                });
+            }
+            
+            for peer_index in 0..engine.config.settings.peers.len() {
+                let engine = engine.clone();
+                timers.spawn(async move {
+                    engine.run_peer_poller(peer_index).await;
+                });
+            }
+            
+            while let Some(result) = timers.join_next().await {
+                if let Err(e) = result {
+                    error!("Service timer task failed: {}", e);
+                }
             }
         })
     }
 
-    async fn check_all_services(&self) {
+    async fn run_service_timer(&self, host_index: usize, service_index: usize, interval: Duration, ramp: Duration) {
+        let host = &self.config.hosts[host_index];
+        let service = &host.services[service_index];
+        let key = ServiceCheck::key_for(host_index, service_index, host, service);
+        
+        // Offsetting the start shifts every later tick too, spreading services
+        // that share an interval across it
+        let jitter = self.config.settings.check_jitter_ms.min(interval.as_millis() as u64);
+        let delay = ramp + Duration::from_millis(if jitter > 0 { fastrand::u64(0..=jitter) } else { 0 });
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        
+        let mut interval_timer = tokio::time::interval(interval);
+        // Don't fire a burst of catch-up checks if one ran past the interval
+        interval_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        
+        // The first tick completes immediately, giving the initial check
+        let mut first = true;
+        loop {
+            interval_timer.tick().await;
+            if !first && self.is_paused() {
+                debug!("Monitoring paused, skipping check of {}/{}", host.name, service.name);
+                continue;
+            }
+            first = false;
+            
+            let _permit = match self.check_limit.acquire().await {
+                Ok(permit) => permit,
+                Err(_) => return,
+            };
+            self.check_service(key.clone(), host, service, None).await;
+        }
+    }
+
+    /// Pulls a peer's results every refresh interval, keeping the error in
+    /// their place while it can't be reached.
+    async fn run_peer_poller(&self, peer_index: usize) {
+        let peer = &self.config.settings.peers[peer_index];
+        let mut interval_timer = tokio::time::interval(Duration::from_secs(self.config.settings.refresh_interval));
+        interval_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        
+        loop {
+            interval_timer.tick().await;
+            if self.is_paused() {
+                continue;
+            }
+            
+            let result = peers::fetch(&self.peer_client, peer, PEER_TIMEOUT).await.map_err(|e| format!("{:#}", e));
+            let mut peer_results = self.peer_results.write().await;
+            match &result {
+                Ok(checks) => debug!("Pulled {} results from peer {}", checks.len(), peer.name),
+                // Only log when the peer goes away, not on every retry
+                Err(e) if !matches!(peer_results.get(&peer.name), Some(Err(_))) => {
+                    warn!("Peer {} unreachable: {}", peer.name, e)
+                }
+                Err(_) => {}
+            }
+            Arc::make_mut(&mut peer_results).insert(peer.name.clone(), result);
+            self.version.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Checks one service right away, outside its timer.
+    pub async fn check_one(&self, host_index: usize, service_index: usize) {
+        let Some(host) = self.config.hosts.get(host_index) else {
+            return;
+        };
+        let Some(service) = host.services.get(service_index) else {
+            return;
+        };
+        
+        let _permit = match self.check_limit.acquire().await {
+            Ok(permit) => permit,
+            Err(_) => return,
+        };
+        let key = ServiceCheck::key_for(host_index, service_index, host, service);
+        self.check_service(key, host, service, None).await;
+    }
+
+    /// Checks every service on one host right away, in parallel.
+    pub async fn check_host(&self, host_index: usize) {
+        let Some(host) = self.config.hosts.get(host_index) else {
+            return;
+        };
+        
+        let tasks: Vec<_> = (0..host.services.len())
+            .map(|service_index| {
+                let engine = self.clone();
+                tokio::spawn(async move { engine.check_one(host_index, service_index).await })
+            })
+            .collect();
+        for task in tasks {
+            if let Err(e) = task.await {
+                error!("Service check task failed: {}", e);
+            }
+        }
+    }
+
+    /// Runs a single check cycle and returns every result, ordered by host and service.
+    pub async fn check_once(&self) -> Vec<ServiceCheck> {
+        self.check_all_services().await;
+        self.get_sorted_statuses().await
+    }
+
+    /// Current statuses ordered by host, then service name.
+    pub async fn get_sorted_statuses(&self) -> Vec<ServiceCheck> {
+        let mut statuses: Vec<_> = self.get_statuses().await.values().cloned().collect();
+        statuses.sort_by(|a, b| {
+            a.host_name
+                .cmp(&b.host_name)
+                .then(a.service_name.cmp(&b.service_name))
+        });
+        statuses
+    }
+
+    /// Runs a single check cycle, for manual refreshes and `check_once`. Its
+    /// results are published together once every check finishes; the
+    /// per-service timers publish each result as it arrives instead.
+    pub async fn check_all_services(&self) {
+        if self.cycle_running.swap(true, Ordering::SeqCst) {
+            warn!("Previous check cycle is still running, not starting another");
+            return;
+        }
+        let _running = CycleGuard(&self.cycle_running);
         debug!("Starting service health checks");
+        let cycle_start = Instant::now();
         
+        let results = CycleResults::default();
         let mut tasks = Vec::new();
         
-        for host in &self.config.hosts {
-            for service in &host.services {
+        for (host_index, host) in self.config.hosts.iter().enumerate() {
+            for service_index in 0..host.services.len() {
                 let engine = self.clone();
-                let host = host.clone();
-                let service = service.clone();
+                let results = results.clone();
                 
                 let task = tokio::spawn(async move {
-                    engine.check_service(&host, &service).await;
+                    // Queue behind in-flight checks once the limit is reached
+                    let _permit = match engine.check_limit.acquire().await {
+                        Ok(permit) => permit,
+                        Err(_) => return,
+                    };
+                    let host = &engine.config.hosts[host_index];
+                    let service = &host.services[service_index];
+                    let key = ServiceCheck::key_for(host_index, service_index, host, service);
+                    engine.check_service(key, host, service, Some(&results)).await;
                 });
                 
                 tasks.push(task);
@@ -120,127 +753,1214 @@ impl MonitorEngine {
             }
         }
         
-        debug!("Completed service health checks");
+        let results = std::mem::take(&mut *results.lock().unwrap_or_else(|e| e.into_inner()));
+        if !results.is_empty() {
+            let mut statuses = self.statuses.write().await;
+            let next = Arc::make_mut(&mut statuses);
+            for mut check in results {
+                // Keep a runtime disable made while the cycle ran
+                check.disabled = next.get(&check.key).is_some_and(|previous| previous.disabled);
+                next.insert(check.key.clone(), check);
+            }
+            self.version.fetch_add(1, Ordering::SeqCst);
+            *self.last_result_at.lock().unwrap_or_else(|e| e.into_inner()) = Utc::now();
+        }
+        
+        debug!("Completed service health checks in {}ms", cycle_start.elapsed().as_millis());
     }
 
-    async fn check_service(&self, host: &Host, service: &Service) {
-        let key = format!("{}:{}:{}", host.name, service.name, service.port);
-        let mut check = ServiceCheck::new(host, service);
+    /// Checks one service and records the result, into `cycle` when it is
+    /// part of a cycle published as a whole.
+    async fn check_service(&self, key: String, host: &Host, service: &Service, cycle: Option<&CycleResults>) {
+        if !service.enabled || self.is_disabled(&key).await {
+            debug!("Checks of {}/{} are disabled, skipping", host.name, service.name);
+            return;
+        }
+        self.timings.lock().unwrap_or_else(|e| e.into_inner()).start_check(&key);
         
-        let start_time = Instant::now();
+        let mut check = ServiceCheck::new(key.clone(), host, service);
         
-        match service.protocol {
-            Protocol::Tcp => {
-                let result = self.check_tcp(&host.address, service.port, service.timeout).await;
-                check.status = result.0;
-                check.error_message = result.1;
+        if service.in_maintenance(Utc::now(), &self.config.settings.timezone) {
+            check.status = ServiceStatus::Maintenance;
+            check.raw_status = ServiceStatus::Maintenance;
+            check.detail = Some("In scheduled maintenance window".to_string());
+            self.streaks.lock().await.remove(&key);
+            self.slow_streaks.lock().await.remove(&key);
+            self.store_check(key, check, false, cycle).await;
+            return;
+        }
+        
+        // No point probing, or alerting, while what this service needs is down
+        if let Some(parent) = &service.depends_on {
+            if self.is_down(&host.name, parent).await {
+                check.error_message = Some(format!("Parent {} down", parent));
+                check.last_check = Utc::now();
+                self.store_check(key, check, false, cycle).await;
+                return;
+            }
+        }
+        
+        let attempts = service.retries + 1;
+        let mut attempt = 1;
+        let check_start = Instant::now();
+        
+        // Retry failed probes before recording the final outcome
+        let (result, response_time) = loop {
+            let start_time = Instant::now();
+            let result = self.probe(host, service).await;
+            let response_time = start_time.elapsed();
+            
+            if result.status != ServiceStatus::Down || attempt >= attempts {
+                break (result, response_time);
+            }
+            
+            debug!(
+                "Check for {}/{} failed (attempt {}/{}), retrying in {}ms",
+                host.name, service.name, attempt, attempts, service.retry_delay_ms
+            );
+            attempt += 1;
+            tokio::time::sleep(Duration::from_millis(service.retry_delay_ms)).await;
+        };
+        
+        check.raw_status = result.status;
+        check.error_message = if service.retries > 0 {
+            result.error.map(|e| format!("{} (after {} attempts)", e, attempt))
+        } else {
+            result.error
+        };
+        self.apply_thresholds(&mut check, service).await;
+        check.detail = result.detail;
+        check.cert_expiry = result.cert_expiry;
+        check.http_version = result.http_version;
+        check.headers = result.headers;
+        check.response_time = if result.reused_connection { None } else { Some(response_time) };
+        check.last_check = Utc::now();
+        self.apply_slow_threshold(&mut check, service).await;
+        let interval = Duration::from_secs(self.config.service_refresh_interval(host, service));
+        self.timings.lock().unwrap_or_else(|e| e.into_inner()).checks.insert(key.clone(), (check_start.elapsed(), interval));
+        self.store_check(key, check, true, cycle).await;
+    }
+
+    async fn is_disabled(&self, key: &str) -> bool {
+        self.statuses.read().await.get(key).is_some_and(|check| check.disabled)
+    }
+
+    /// Switches checks of a service off or back on at runtime. The flag lives
+    /// on its stored result, so the state file keeps it across restarts.
+    pub async fn set_enabled(&self, key: &str, enabled: bool) {
+        let mut statuses = self.statuses.write().await;
+        if statuses.get(key).is_some_and(|check| check.disabled == enabled) {
+            if let Some(check) = Arc::make_mut(&mut statuses).get_mut(key) {
+                check.disabled = !enabled;
+                info!(
+                    "{} checks of {}/{}",
+                    if enabled { "Enabled" } else { "Disabled" },
+                    check.host_name,
+                    check.service_name
+                );
+                self.version.fetch_add(1, Ordering::SeqCst);
             }
-            Protocol::Udp => {
-                let result = self.check_udp(&host.address, service.port, service.timeout).await;
-                check.status = result.0;
-                check.error_message = result.1;
+        }
+        if !enabled {
+            self.timings.lock().unwrap_or_else(|e| e.into_inner()).forget(key);
+        }
+    }
+
+    async fn is_down(&self, host_name: &str, service_name: &str) -> bool {
+        self.statuses.read().await.values().any(|check| {
+            check.host_name == host_name && check.service_name == service_name && check.status == ServiceStatus::Down
+        })
+    }
+
+    /// Sets the effective status from the raw one: a service only goes down
+    /// after `failure_threshold` failures in a row, and only comes back after
+    /// `success_threshold` successes in a row.
+    async fn apply_thresholds(&self, check: &mut ServiceCheck, service: &Service) {
+        let failing = check.raw_status == ServiceStatus::Down;
+        let count = {
+            let mut streaks = self.streaks.lock().await;
+            let streak = streaks.entry(check.key.clone()).or_insert(Streak { failing, count: 0 });
+            if streak.failing != failing {
+                *streak = Streak { failing, count: 0 };
             }
-            Protocol::Http => {
-                let result = self.check_http(&host.address, service.port, &service.path, service.timeout).await;
-                check.status = result.0;
-                check.error_message = result.1;
+            streak.count += 1;
+            streak.count
+        };
+        
+        let previous = match self.statuses.read().await.get(&check.key) {
+            Some(previous) if !matches!(previous.status, ServiceStatus::Maintenance | ServiceStatus::Pending) => {
+                previous.status.clone()
             }
-            Protocol::Https => {
-                let result = self.check_https(&host.address, service.port, &service.path, service.timeout).await;
-                check.status = result.0;
-                check.error_message = result.1;
+            _ => ServiceStatus::Unknown,
+        };
+        
+        check.status = check.raw_status.clone();
+        if failing && previous != ServiceStatus::Down && count < service.failure_threshold {
+            check.status = previous;
+            check.error_message = check.error_message.take().map(|e| {
+                format!("{} ({}/{} failures before down)", e, count, service.failure_threshold)
+            });
+        } else if !failing && previous == ServiceStatus::Down && count < service.success_threshold {
+            check.status = ServiceStatus::Down;
+            check.error_message = Some(format!(
+                "Recovering ({}/{} successes before up)",
+                count, service.success_threshold
+            ));
+        }
+    }
+
+    /// Reports an up service as degraded once `slow_threshold` checks in a row
+    /// took longer than `warn_response_ms`, so a single slow sample doesn't
+    /// alert but a sustained slowdown does.
+    async fn apply_slow_threshold(&self, check: &mut ServiceCheck, service: &Service) {
+        let Some(warn_ms) = service.warn_response_ms else {
+            return;
+        };
+        let millis = check.response_time.unwrap_or_default().as_millis();
+        let slow = check.raw_status != ServiceStatus::Down && millis > u128::from(warn_ms);
+        
+        let count = {
+            let mut slow_streaks = self.slow_streaks.lock().await;
+            if slow {
+                let count = slow_streaks.entry(check.key.clone()).or_insert(0);
+                *count += 1;
+                *count
+            } else {
+                slow_streaks.remove(&check.key);
+                0
             }
+        };
+        
+        if count >= service.slow_threshold && check.status == ServiceStatus::Up {
+            check.status = ServiceStatus::Degraded;
+            check.error_message = Some(format!(
+                "Slow response: {}ms, over {}ms for {} checks in a row",
+                millis, warn_ms, count
+            ));
         }
+    }
+
+    /// Records a finished check and, if `alert` is set, alerts on any status
+    /// change. With `cycle`, the result is held back for the cycle to publish;
+    /// otherwise it is published at once.
+    async fn store_check(&self, key: String, mut check: ServiceCheck, alert: bool, cycle: Option<&CycleResults>) {
+        check.flapping = self.record_sample(&check).await;
+        let enabled = self.statuses.read().await.values().filter(|check| !check.disabled).count();
+        self.timings.lock().unwrap_or_else(|e| e.into_inner()).record_round(&key, enabled);
         
-        check.response_time = start_time.elapsed();
-        check.last_check = Utc::now();
+        if let Some(history) = &self.history {
+            history.record(&check);
+        }
         
-        // Update status in shared map
-        let mut statuses = self.statuses.write().await;
-        statuses.insert(key, check);
+        // Update status in shared map, remembering what it replaced
+        let previous = {
+            let mut statuses = self.statuses.write().await;
+            check.status_since = match statuses.get(&key) {
+                Some(previous) if previous.status == check.status => previous.status_since,
+                _ => check.last_check,
+            };
+            match cycle {
+                Some(cycle) => {
+                    cycle.lock().unwrap_or_else(|e| e.into_inner()).push(check.clone());
+                    statuses.get(&key).cloned()
+                }
+                // Copies the map only while a reader still holds the old snapshot
+                None => Arc::make_mut(&mut statuses).insert(key.clone(), check.clone()),
+            }
+        };
+        if cycle.is_none() {
+            self.version.fetch_add(1, Ordering::SeqCst);
+            *self.last_result_at.lock().unwrap_or_else(|e| e.into_inner()) = Utc::now();
+        }
+        
+        // Once flapping stops, the changes held back are alerted as one, from
+        // the status last alerted to where the service settled
+        let held = if check.flapping { None } else { self.flap_held.lock().await.remove(&key) };
+        if let Some(held) = &held {
+            if *held != check.status && alert {
+                let settled = Transition {
+                    host: check.host_name.clone(),
+                    service: check.service_name.clone(),
+                    old_status: held.clone(),
+                    new_status: check.status.clone(),
+                    timestamp: check.last_check,
+                    error: check.error_message.clone(),
+                };
+                self.handle_transition(&key, settled).await;
+            }
+        }
+        
+        // A result restored from the last run isn't a baseline worth alerting on,
+        // and neither is the placeholder from before the first check
+        let Some(previous) = previous.filter(|previous| !previous.restored && previous.status != ServiceStatus::Pending) else {
+            return;
+        };
+        if previous.status == check.status {
+            return;
+        }
+        
+        let transition = Transition {
+            host: check.host_name,
+            service: check.service_name,
+            old_status: previous.status,
+            new_status: check.status,
+            timestamp: check.last_check,
+            error: check.error_message,
+        };
+        self.record_transition(transition.clone());
+        
+        if check.flapping {
+            self.flap_held.lock().await.entry(key.clone()).or_insert_with(|| transition.old_status.clone());
+            info!(
+                "Status change for {}/{} not alerted, service is flapping ({} -> {})",
+                transition.host,
+                transition.service,
+                transition.old_status.as_str(),
+                transition.new_status.as_str()
+            );
+        } else if held.is_some() {
+            // Covered by the alert for the held-back changes above
+        } else if transition.old_status == ServiceStatus::Maintenance && transition.new_status != ServiceStatus::Down {
+            // Like a first result, the end of a window is only worth alerting on if it's down
+            info!(
+                "Status change for {}/{} not alerted, maintenance ended ({} -> {})",
+                transition.host,
+                transition.service,
+                transition.old_status.as_str(),
+                transition.new_status.as_str()
+            );
+        } else if !alert {
+            info!(
+                "Status change for {}/{} not alerted ({} -> {}): {}",
+                transition.host,
+                transition.service,
+                transition.old_status.as_str(),
+                transition.new_status.as_str(),
+                transition.error.as_deref().or(check.detail.as_deref()).unwrap_or("")
+            );
+        } else {
+            self.handle_transition(&key, transition).await;
+        }
     }
 
-    async fn check_tcp(&self, address: &str, port: u16, timeout: u64) -> (ServiceStatus, Option<String>) {
-        let addr = format!("{}:{}", address, port);
-        let timeout_duration = Duration::from_secs(timeout);
+    /// Adds a status change to the event log, dropping the oldest once full.
+    fn record_transition(&self, transition: Transition) {
+        let mut transitions = self.transitions.lock().unwrap_or_else(|e| e.into_inner());
+        transitions.push_back(transition);
+        if transitions.len() > EVENT_LOG_SIZE {
+            transitions.pop_front();
+        }
+    }
+
+    pub fn check_timing(&self) -> CheckTiming {
+        let timings = self.timings.lock().unwrap_or_else(|e| e.into_inner());
+        CheckTiming {
+            last_cycle: timings.last_cycle,
+            slowest: timings
+                .checks
+                .iter()
+                .max_by_key(|(_, (duration, _))| *duration)
+                .map(|(key, (duration, interval))| (key.clone(), *duration, *interval)),
+        }
+    }
+
+    /// Status changes seen this run, oldest first.
+    pub fn recent_transitions(&self) -> VecDeque<Transition> {
+        self.transitions.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Appends the result to the service's recent samples and reports whether
+    /// it has changed state more than `flap_threshold` times within the window.
+    async fn record_sample(&self, check: &ServiceCheck) -> bool {
+        let mut samples = self.samples.write().await;
+        let recent = samples.entry(check.key.clone()).or_default();
+        recent.push_back(CheckSample {
+            timestamp: check.last_check,
+            status: check.status.clone(),
+            response_time: check.response_time,
+        });
+        if recent.len() > RECENT_SAMPLES {
+            recent.pop_front();
+        }
+        
+        let threshold = self.config.settings.flap_threshold;
+        if threshold == 0 {
+            return false;
+        }
+        
+        let window = self.config.settings.flap_window.min(recent.len());
+        let changes = recent
+            .iter()
+            .skip(recent.len() - window)
+            .zip(recent.iter().skip(recent.len() - window + 1))
+            .filter(|(a, b)| a.status != b.status)
+            .count();
+        changes > threshold as usize
+    }
+
+    async fn handle_transition(&self, key: &str, transition: Transition) {
+        info!("Status change: {}", transition.summary());
         
-        match tokio::time::timeout(timeout_duration, tokio::net::TcpStream::connect(&addr)).await {
+        if !self.alert_allowed(key, &transition.new_status).await {
+            debug!("Alert for {}/{} skipped, still in cooldown", transition.host, transition.service);
+            return;
+        }
+        
+        if let Some(url) = &self.config.settings.webhook_url {
+            alerts::send_webhook(&self.http_client, url, &transition).await;
+        }
+        
+        if self.config.settings.notifications {
+            alerts::send_desktop_notification(transition).await;
+        }
+    }
+
+    /// Applies `alert_cooldown`: at most one alert per service per window,
+    /// except recoveries, which always go out.
+    async fn alert_allowed(&self, key: &str, new_status: &ServiceStatus) -> bool {
+        let cooldown = Duration::from_secs(self.config.settings.alert_cooldown);
+        if cooldown.is_zero() {
+            return true;
+        }
+        
+        let mut last_alerts = self.last_alerts.lock().await;
+        let recovery = *new_status == ServiceStatus::Up;
+        if !recovery && last_alerts.get(key).is_some_and(|sent| sent.elapsed() < cooldown) {
+            return false;
+        }
+        last_alerts.insert(key.to_string(), Instant::now());
+        true
+    }
+
+    /// The address to connect to for `address`: with `dns_cache_ttl` set, a
+    /// host name is resolved once and its IP reused until the TTL runs out,
+    /// so flaky resolvers don't show up as service failures. IP addresses
+    /// pass through as they are.
+    async fn resolve_target(&self, address: &str, timeout: Duration) -> Result<String, String> {
+        let ttl = Duration::from_secs(self.config.settings.dns_cache_ttl);
+        if ttl.is_zero() || address.parse::<IpAddr>().is_ok() {
+            return Ok(address.to_string());
+        }
+        
+        if let Some((ip, resolved_at)) = self.resolved.lock().await.get(address) {
+            if resolved_at.elapsed() < ttl {
+                return Ok(ip.to_string());
+            }
+        }
+        
+        let addresses = match tokio::time::timeout(timeout, tokio::net::lookup_host((address, 0))).await {
+            Ok(Ok(addresses)) => addresses,
+            Ok(Err(e)) => return Err(format!("DNS resolution of {} failed: {}", address, e)),
+            Err(_) => return Err(format!("DNS resolution of {} timed out", address)),
+        };
+        // Only an address of the bind address's family is reachable from it
+        let ip = addresses
+            .map(|socket| socket.ip())
+            .find(|ip| self.bind_address.is_none_or(|local| local.is_ipv4() == ip.is_ipv4()))
+            .ok_or_else(|| format!("DNS resolution of {} returned no usable address", address))?;
+        
+        debug!("Resolved {} to {}, caching for {}s", address, ip, ttl.as_secs());
+        self.resolved.lock().await.insert(address.to_string(), (ip, Instant::now()));
+        Ok(ip.to_string())
+    }
+
+    /// Whether an HTTP check of `host` skips the configured proxies: when the
+    /// host opts out, or its name resolves into an IP range in `no_proxy`.
+    /// Names and IP literals listed there are matched by reqwest itself.
+    async fn bypasses_proxy(&self, host: &Host, service: &Service) -> bool {
+        let settings = &self.config.settings;
+        if settings.http_proxy.is_none() && settings.https_proxy.is_none() {
+            return false;
+        }
+        if host.bypass_proxy {
+            return true;
+        }
+        if settings.no_proxy_networks().next().is_none() || host.address.parse::<IpAddr>().is_ok() {
+            return false;
+        }
+        
+        // Cached addresses are reused; otherwise look the name up. If that
+        // fails, the proxy may still be able to resolve it
+        let timeout = service.connect_timeout();
+        let ip = match self.resolve_target(&host.address, timeout).await.ok().and_then(|address| address.parse().ok()) {
+            Some(ip) => Some(ip),
+            None => match tokio::time::timeout(timeout, tokio::net::lookup_host((host.address.as_str(), 0))).await {
+                Ok(Ok(mut addresses)) => addresses.next().map(|socket| socket.ip()),
+                _ => None,
+            },
+        };
+        ip.is_some_and(|ip| settings.no_proxy_covers(ip))
+    }
+
+    async fn probe(&self, host: &Host, service: &Service) -> ProbeResult {
+        // URL-based checks keep the name, which TLS and virtual hosts need
+        let address = match service.protocol {
+            Protocol::Tcp | Protocol::Udp | Protocol::Banner | Protocol::Dns | Protocol::Grpc => {
+                match self.resolve_target(&host.address, service.connect_timeout()).await {
+                    Ok(address) => address,
+                    Err(e) => return (ServiceStatus::Down, Some(e)).into(),
+                }
+            }
+            _ => host.address.clone(),
+        };
+        
+        match service.protocol {
+            Protocol::Tcp if service.send.is_some() || service.expect.is_some() => {
+                self.check_tcp_payload(&address, service).await
+            }
+            Protocol::Tcp if service.keep_alive => {
+                self.check_tcp_persistent(&address, service.port, service.connect_timeout()).await
+            }
+            Protocol::Tcp => self.check_tcp(&address, service.port, service.connect_timeout(), service.verify_open).await.into(),
+            Protocol::Udp => self.check_udp(&address, service.port, service.timeout).await.into(),
+            Protocol::Http => self.check_http(&host.address, service, self.bypasses_proxy(host, service).await).await,
+            Protocol::Https => self.check_https(&host.address, service, self.bypasses_proxy(host, service).await).await,
+            Protocol::Dns => self.check_dns(&address, service.port, &service.query, service.timeout).await,
+            Protocol::Grpc => self.check_grpc(&address, service.port, &service.grpc_service, service.timeout).await.into(),
+            Protocol::Banner => self.check_banner(&address, service.port, &service.expected_banner, service.timeout).await,
+            Protocol::Ws => self.check_websocket(build_url("ws", 80, &host.address, service), &host.address, service).await.into(),
+            Protocol::Wss => self.check_websocket(build_url("wss", 443, &host.address, service), &host.address, service).await.into(),
+            Protocol::Unix => self.check_unix(&host.address, service.connect_timeout()).await.into(),
+        }
+    }
+
+    #[cfg(unix)]
+    async fn check_unix(&self, path: &str, timeout: Duration) -> (ServiceStatus, Option<String>) {
+        match tokio::time::timeout(timeout, tokio::net::UnixStream::connect(path)).await {
             Ok(Ok(_)) => (ServiceStatus::Up, None),
-            Ok(Err(e)) => (ServiceStatus::Down, Some(e.to_string())),
+            Ok(Err(e)) => (ServiceStatus::Down, Some(format!("{}: {}", path, e))),
             Err(_) => (ServiceStatus::Down, Some("Connection timeout".to_string())),
         }
     }
 
+    #[cfg(not(unix))]
+    async fn check_unix(&self, _path: &str, _timeout: Duration) -> (ServiceStatus, Option<String>) {
+        (ServiceStatus::Unknown, Some("Unix domain sockets are not supported on this platform".to_string()))
+    }
+
+    /// With `verify_open`, a connection also has to survive `VERIFY_OPEN_WAIT`
+    /// without being reset or closed by the peer.
+    async fn check_tcp(&self, address: &str, port: u16, timeout: Duration, verify_open: bool) -> (ServiceStatus, Option<String>) {
+        let mut stream = match tokio::time::timeout(timeout, connect_tcp(address, port, self.bind_address)).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => return (ServiceStatus::Down, Some(e.to_string())),
+            Err(_) => return (ServiceStatus::Down, Some("Connection timeout".to_string())),
+        };
+        if !verify_open {
+            return (ServiceStatus::Up, None);
+        }
+        
+        // Silence is fine, and so is a server that greets first
+        let mut buffer = [0u8; 64];
+        match tokio::time::timeout(VERIFY_OPEN_WAIT, stream.read(&mut buffer)).await {
+            Err(_) | Ok(Ok(1..)) => (ServiceStatus::Up, None),
+            Ok(Ok(_)) => (ServiceStatus::Degraded, Some("Connection closed right after connecting".to_string())),
+            Ok(Err(e)) => (ServiceStatus::Down, Some(format!("Connection dropped right after connecting: {}", e))),
+        }
+    }
+
+    /// Writes `send`, then reads until the response contains `expect`, the
+    /// server closes the connection or the read limit is reached.
+    async fn check_tcp_payload(&self, address: &str, service: &Service) -> ProbeResult {
+        let send = service.send.as_deref().map(unescape).transpose();
+        let expect = service.expect.as_deref().map(unescape).transpose();
+        let (send, expect) = match (send, expect) {
+            (Ok(send), Ok(expect)) => (send, expect),
+            (Err(e), _) | (_, Err(e)) => return (ServiceStatus::Down, Some(format!("Invalid payload: {}", e))).into(),
+        };
+        
+        let mut stream = match tokio::time::timeout(service.connect_timeout(), connect_tcp(address, service.port, self.bind_address)).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => return (ServiceStatus::Down, Some(e.to_string())).into(),
+            Err(_) => return (ServiceStatus::Down, Some("Connection timeout".to_string())).into(),
+        };
+        
+        let exchange = async {
+            if let Some(send) = &send {
+                stream.write_all(send).await?;
+            }
+            let mut response = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while response.len() < MAX_PAYLOAD_RESPONSE {
+                if expect.as_ref().is_some_and(|expect| contains_bytes(&response, expect)) {
+                    break;
+                }
+                let read = stream.read(&mut buffer).await?;
+                if read == 0 {
+                    break;
+                }
+                response.extend_from_slice(&buffer[..read]);
+            }
+            Ok::<_, std::io::Error>(response)
+        };
+        
+        let response = match tokio::time::timeout(service.read_timeout(), exchange).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(e)) => return (ServiceStatus::Down, Some(format!("Payload exchange failed: {}", e))).into(),
+            Err(_) => return (ServiceStatus::Down, Some("Timed out waiting for response".to_string())).into(),
+        };
+        
+        let shown = String::from_utf8_lossy(&response).trim().to_string();
+        let mut result: ProbeResult = match (&expect, &service.expect) {
+            (Some(expect), Some(raw)) if !contains_bytes(&response, expect) => (
+                ServiceStatus::Down,
+                Some(format!("Response did not contain '{}'", raw)),
+            )
+                .into(),
+            _ => (ServiceStatus::Up, None).into(),
+        };
+        result.detail = (!shown.is_empty()).then_some(shown);
+        result
+    }
+
+    /// Keeps the connection open between checks and only reconnects once the
+    /// peer has closed it, so steady-state checks skip the handshake. Nothing
+    /// is sent over a reused connection, so it has no response time; TCP
+    /// keepalive notices a peer that vanished without closing it.
+    async fn check_tcp_persistent(&self, address: &str, port: u16, timeout: Duration) -> ProbeResult {
+        let addr = socket_address(address, port);
+        
+        let existing = self.connections.lock().await.remove(&addr);
+        if let Some(stream) = existing {
+            if connection_is_open(&stream) {
+                self.connections.lock().await.insert(addr, stream);
+                let mut result: ProbeResult = (ServiceStatus::Up, None).into();
+                result.detail = Some("Reused open connection".to_string());
+                result.reused_connection = true;
+                return result;
+            }
+            debug!("Pooled connection to {} was closed, reconnecting", addr);
+        }
+        
+        match tokio::time::timeout(timeout, connect_tcp(address, port, self.bind_address)).await {
+            Ok(Ok(stream)) => {
+                let keepalive = socket2::TcpKeepalive::new()
+                    .with_time(POOLED_KEEPALIVE_IDLE)
+                    .with_interval(POOLED_KEEPALIVE_IDLE);
+                if let Err(e) = socket2::SockRef::from(&stream).set_tcp_keepalive(&keepalive) {
+                    debug!("Could not enable keepalive on connection to {}: {}", addr, e);
+                }
+                self.connections.lock().await.insert(addr, stream);
+                let mut result: ProbeResult = (ServiceStatus::Up, None).into();
+                result.detail = Some("Opened new connection".to_string());
+                result
+            }
+            Ok(Err(e)) => (ServiceStatus::Down, Some(e.to_string())).into(),
+            Err(_) => (ServiceStatus::Down, Some("Connection timeout".to_string())).into(),
+        }
+    }
+
+    /// Connects and reads the server greeting, so a port that accepts
+    /// connections but doesn't speak the protocol isn't reported as up.
+    async fn check_banner(&self, address: &str, port: u16, expected: &Option<String>, timeout: u64) -> ProbeResult {
+        let timeout_duration = Duration::from_secs(timeout);
+        
+        let read_banner = async {
+            let mut stream = connect_tcp(address, port, self.bind_address).await?;
+            let mut buffer = [0u8; 512];
+            let read = stream.read(&mut buffer).await?;
+            Ok::<_, std::io::Error>(String::from_utf8_lossy(&buffer[..read]).into_owned())
+        };
+        
+        let banner = match tokio::time::timeout(timeout_duration, read_banner).await {
+            Ok(Ok(banner)) => banner.lines().next().unwrap_or("").trim().to_string(),
+            Ok(Err(e)) => return (ServiceStatus::Down, Some(e.to_string())).into(),
+            Err(_) => return (ServiceStatus::Down, Some("Timed out waiting for banner".to_string())).into(),
+        };
+        
+        if banner.is_empty() {
+            return (ServiceStatus::Down, Some("Connection closed without a banner".to_string())).into();
+        }
+        
+        let (status, error) = match expected {
+            Some(prefix) if !banner.starts_with(prefix.as_str()) => (
+                ServiceStatus::Down,
+                Some(format!("Unexpected banner '{}' (expected '{}')", banner, prefix)),
+            ),
+            _ => (ServiceStatus::Up, None),
+        };
+        
+        ProbeResult {
+            status,
+            error,
+            detail: Some(banner),
+            cert_expiry: None,
+            http_version: None,
+            headers: Vec::new(),
+            reused_connection: false,
+        }
+    }
+
+    /// Up once the upgrade handshake succeeds and, with `ws_ping`, the server
+    /// answers a ping.
+    async fn check_websocket(&self, url: String, address: &str, service: &Service) -> (ServiceStatus, Option<String>) {
+        let timeout_duration = Duration::from_secs(service.timeout);
+        
+        let handshake = async {
+            let connector = tls_connector(self.config.settings.accept_invalid_certs).map_err(|e| e.to_string())?;
+            let stream = connect_tcp(address, service.port, self.bind_address).await.map_err(|e| e.to_string())?;
+            let (mut socket, _) = tokio_tungstenite::client_async_tls_with_config(
+                url.as_str(),
+                stream,
+                None,
+                Some(tokio_tungstenite::Connector::NativeTls(connector)),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+            
+            if service.ws_ping {
+                socket.send(Message::Ping(Vec::new())).await.map_err(|e| e.to_string())?;
+                // The server may push other messages before answering
+                loop {
+                    match socket.next().await {
+                        Some(Ok(Message::Pong(_))) => break,
+                        Some(Ok(_)) => continue,
+                        Some(Err(e)) => return Err(e.to_string()),
+                        None => return Err("Connection closed before pong".to_string()),
+                    }
+                }
+            }
+            
+            let _ = socket.close(None).await;
+            Ok(())
+        };
+        
+        match tokio::time::timeout(timeout_duration, handshake).await {
+            Ok(Ok(())) => (ServiceStatus::Up, None),
+            Ok(Err(e)) => (ServiceStatus::Down, Some(e)),
+            Err(_) => (ServiceStatus::Down, Some("WebSocket handshake timeout".to_string())),
+        }
+    }
+
     async fn check_udp(&self, _address: &str, _port: u16, timeout: u64) -> (ServiceStatus, Option<String>) {
         // UDP checks are more complex - for now we'll do a basic socket test
         let timeout_duration = Duration::from_secs(timeout);
         
-        match tokio::time::timeout(timeout_duration, tokio::net::UdpSocket::bind("0.0.0.0:0")).await {
+        let local = SocketAddr::new(self.bind_address.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)), 0);
+        match tokio::time::timeout(timeout_duration, tokio::net::UdpSocket::bind(local)).await {
             Ok(Ok(_)) => (ServiceStatus::Up, None),
             Ok(Err(e)) => (ServiceStatus::Down, Some(e.to_string())),
             Err(_) => (ServiceStatus::Down, Some("UDP socket creation timeout".to_string())),
         }
     }
 
-    async fn check_http(&self, address: &str, port: u16, path: &Option<String>, timeout: u64) -> (ServiceStatus, Option<String>) {
-        let url = if port == 80 {
-            format!("http://{}", address)
-        } else {
-            format!("http://{}:{}", address, port)
-        };
-        
-        let url = if let Some(path) = path {
-            format!("{}{}", url, path)
-        } else {
-            url
-        };
+    async fn check_http(&self, address: &str, service: &Service, direct: bool) -> ProbeResult {
+        let url = build_url("http", 80, address, service);
         
-        let timeout_duration = Duration::from_secs(timeout);
+        let timeout_duration = service.connect_timeout() + service.read_timeout();
         
-        match tokio::time::timeout(timeout_duration, self.http_client.get(&url).send()).await {
+        let mut http_version = None;
+        let mut headers = Vec::new();
+        let mut result: ProbeResult = match tokio::time::timeout(timeout_duration, self.build_request(&url, service, direct).send()).await {
             Ok(Ok(response)) => {
+                http_version = Some(format!("{:?}", response.version()));
+                headers = capture_headers(&response, service);
                 if response.status().is_success() {
-                    (ServiceStatus::Up, None)
+                    match self.check_body(response, service).await {
+                        Some((status, error)) => (status, Some(error)),
+                        None => (ServiceStatus::Up, None),
+                    }
                 } else {
                     (ServiceStatus::Down, Some(format!("HTTP {}", response.status())))
                 }
             }
-            Ok(Err(e)) => (ServiceStatus::Down, Some(e.to_string())),
+            Ok(Err(e)) => (ServiceStatus::Down, Some(describe_request_error(&e, "HTTP"))),
             Err(_) => (ServiceStatus::Down, Some("HTTP request timeout".to_string())),
         }
+        .into();
+        result.http_version = http_version;
+        result.headers = headers;
+        result
     }
 
-    async fn check_https(&self, address: &str, port: u16, path: &Option<String>, timeout: u64) -> (ServiceStatus, Option<String>) {
-        let url = if port == 443 {
-            format!("https://{}", address)
-        } else {
-            format!("https://{}:{}", address, port)
+    async fn check_https(&self, address: &str, service: &Service, direct: bool) -> ProbeResult {
+        let port = service.port;
+        let url = build_url("https", 443, address, service);
+        
+        let timeout_duration = service.connect_timeout() + service.read_timeout();
+        
+        let mut http_version = None;
+        let mut headers = Vec::new();
+        let mut result = match tokio::time::timeout(timeout_duration, self.build_request(&url, service, direct).send()).await {
+            Ok(Ok(response)) => {
+                http_version = Some(format!("{:?}", response.version()));
+                headers = capture_headers(&response, service);
+                if response.status().is_success() {
+                    match self.check_body(response, service).await {
+                        Some((status, error)) => (status, Some(error)).into(),
+                        // The expiry lookup connects directly, which a proxied route can't
+                        None if !direct && self.https_proxied() => (ServiceStatus::Up, None).into(),
+                        None => self.check_certificate(address, port, timeout_duration).await,
+                    }
+                } else {
+                    (ServiceStatus::Down, Some(format!("HTTPS {}", response.status()))).into()
+                }
+            }
+            Ok(Err(e)) => (ServiceStatus::Down, Some(describe_request_error(&e, "HTTPS"))).into(),
+            Err(_) => (ServiceStatus::Down, Some("HTTPS request timeout".to_string())).into(),
+        };
+        result.http_version = http_version;
+        result.headers = headers;
+        result
+    }
+
+    /// Checks the response body against `settings.max_response_bytes` and the
+    /// service's `json_assert`, returning the status and reason on failure.
+    async fn check_body(&self, mut response: reqwest::Response, service: &Service) -> Option<(ServiceStatus, String)> {
+        // HEAD responses have no body to check
+        if service.method == HttpMethod::Head {
+            return None;
+        }
+        
+        let limit = self.config.settings.max_response_bytes;
+        let too_large = || (ServiceStatus::Degraded, format!("Response too large (over {} bytes)", limit));
+        if limit > 0 && response.content_length().is_some_and(|length| length > limit) {
+            return Some(too_large());
+        }
+        
+        let assertion = service.json_assert.as_ref();
+        if limit == 0 && assertion.is_none() {
+            return None;
+        }
+        
+        // Streamed, so a body without a Content-Length is still cut off at the
+        // limit; it is only kept when there is an assertion to evaluate
+        let mut body = Vec::new();
+        let mut received: u64 = 0;
+        let read = async {
+            while let Some(chunk) = response.chunk().await? {
+                received += chunk.len() as u64;
+                if limit > 0 && received > limit {
+                    return Ok(false);
+                }
+                if assertion.is_some() {
+                    body.extend_from_slice(&chunk);
+                }
+            }
+            Ok::<_, reqwest::Error>(true)
+        };
+        match tokio::time::timeout(service.read_timeout(), read).await {
+            Ok(Ok(true)) => {}
+            Ok(Ok(false)) => return Some(too_large()),
+            Ok(Err(e)) => return Some((ServiceStatus::Down, format!("Failed to read response body: {}", e))),
+            Err(_) => return Some((ServiceStatus::Down, "Timed out reading response body".to_string())),
+        }
+        
+        assertion?
+            .evaluate(&String::from_utf8_lossy(&body))
+            .err()
+            .map(|error| (ServiceStatus::Down, error))
+    }
+
+    fn build_request(&self, url: &str, service: &Service, direct: bool) -> reqwest::RequestBuilder {
+        let client = self
+            .check_clients
+            .get(&ClientOptions::for_service(service, &self.config.settings, direct))
+            .unwrap_or_else(|| {
+                // `new` builds a client for every service, so this is a bug
+                error!("No HTTP client prepared for {}, using the default client", service.name);
+                &self.http_client
+            });
+        let method = match service.method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Head => reqwest::Method::HEAD,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Patch => reqwest::Method::PATCH,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+            HttpMethod::Options => reqwest::Method::OPTIONS,
+        };
+        let mut request = client.request(method, url).timeout(service.read_timeout());
+        
+        if let Some(content_type) = &service.content_type {
+            request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+        }
+        
+        for (name, value) in &service.headers {
+            request = request.header(name, value);
+        }
+        
+        if let Some(body) = service.body.as_ref().filter(|_| service.method.allows_body()) {
+            request = request.body(body.clone());
+        }
+        
+        if let Some(auth) = &service.basic_auth {
+            request = request.basic_auth(&auth.username, auth.password.as_ref());
+        }
+        
+        request
+    }
+
+    /// Whether `https` requests not marked direct go through a proxy, either
+    /// `settings.https_proxy` or, when no proxy is configured, `HTTPS_PROXY`.
+    fn https_proxied(&self) -> bool {
+        let settings = &self.config.settings;
+        if settings.http_proxy.is_some() || settings.https_proxy.is_some() {
+            return settings.https_proxy.is_some();
+        }
+        ["HTTPS_PROXY", "https_proxy"].iter().any(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()))
+    }
+
+    /// Grades an otherwise healthy HTTPS service by how soon its certificate expires.
+    async fn check_certificate(&self, address: &str, port: u16, timeout: Duration) -> ProbeResult {
+        let mut result: ProbeResult = (ServiceStatus::Up, None).into();
+        
+        let expiry = match tokio::time::timeout(timeout, fetch_cert_expiry(address, port, self.config.settings.accept_invalid_certs, self.bind_address)).await {
+            Ok(Ok(expiry)) => expiry,
+            Ok(Err(e)) => {
+                debug!("Could not read certificate for {}:{}: {}", address, port, e);
+                return result;
+            }
+            Err(_) => {
+                debug!("Timed out reading certificate for {}:{}", address, port);
+                return result;
+            }
         };
         
-        let url = if let Some(path) = path {
-            format!("{}{}", url, path)
+        let days_left = (expiry - Utc::now()).num_days();
+        let message = format!("cert expires in {} days", days_left);
+        
+        if days_left < i64::from(self.config.settings.cert_warn_days) {
+            result.status = ServiceStatus::Degraded;
+            result.error = Some(message);
         } else {
-            url
+            result.detail = Some(message);
+        }
+        result.cert_expiry = Some(expiry);
+        result
+    }
+
+    async fn check_dns(&self, address: &str, port: u16, query: &Option<String>, timeout: u64) -> ProbeResult {
+        let query = match query {
+            Some(query) => query,
+            None => return (ServiceStatus::Down, Some("No DNS query configured".to_string())).into(),
+        };
+        
+        // The resolver needs a socket address, so look up the server itself first
+        let server: SocketAddr = match tokio::net::lookup_host((address, port)).await {
+            Ok(mut addrs) => match addrs.next() {
+                Some(addr) => addr,
+                None => return (ServiceStatus::Down, Some(format!("Could not resolve DNS server {}", address))).into(),
+            },
+            Err(e) => return (ServiceStatus::Down, Some(format!("Could not resolve DNS server {}: {}", address, e))).into(),
         };
         
+        let resolver_config = ResolverConfig::from_parts(
+            None,
+            vec![],
+            vec![NameServerConfig {
+                bind_addr: self.bind_address.map(|ip| SocketAddr::new(ip, 0)),
+                ..NameServerConfig::new(server, DnsProtocol::Udp)
+            }],
+        );
+        let mut opts = ResolverOpts::default();
+        opts.timeout = Duration::from_secs(timeout);
+        opts.attempts = 1;
+        opts.cache_size = 0;
+        
+        let resolver = TokioAsyncResolver::tokio(resolver_config, opts);
+        
+        match resolver.lookup_ip(query.as_str()).await {
+            Ok(lookup) => {
+                let count = lookup.iter().count();
+                ProbeResult {
+                    status: ServiceStatus::Up,
+                    error: None,
+                    detail: Some(format!("{} resolved to {} address{}", query, count, if count == 1 { "" } else { "es" })),
+                    cert_expiry: None,
+                    http_version: None,
+                    headers: Vec::new(),
+                    reused_connection: false,
+                }
+            }
+            Err(e) => {
+                let message = match e.kind() {
+                    ResolveErrorKind::Timeout => "DNS query timeout".to_string(),
+                    ResolveErrorKind::NoRecordsFound { response_code, .. } => {
+                        format!("No records for {} ({})", query, response_code)
+                    }
+                    _ => e.to_string(),
+                };
+                (ServiceStatus::Down, Some(message)).into()
+            }
+        }
+    }
+
+    async fn check_grpc(&self, address: &str, port: u16, grpc_service: &Option<String>, timeout: u64) -> (ServiceStatus, Option<String>) {
         let timeout_duration = Duration::from_secs(timeout);
         
-        match tokio::time::timeout(timeout_duration, self.http_client.get(&url).send()).await {
-            Ok(Ok(response)) => {
-                if response.status().is_success() {
-                    (ServiceStatus::Up, None)
-                } else {
-                    (ServiceStatus::Down, Some(format!("HTTPS {}", response.status())))
+        let endpoint = match tonic::transport::Endpoint::from_shared(format!("http://{}", socket_address(address, port))) {
+            Ok(endpoint) => endpoint.connect_timeout(timeout_duration).timeout(timeout_duration),
+            Err(e) => return (ServiceStatus::Down, Some(format!("Invalid gRPC endpoint: {}", e))),
+        };
+        
+        // An empty service name asks about the server as a whole
+        let request = HealthCheckRequest {
+            service: grpc_service.clone().unwrap_or_default(),
+        };
+        
+        let check = async {
+            let channel = match self.bind_address {
+                Some(bind_address) => {
+                    let address = address.to_string();
+                    endpoint
+                        .connect_with_connector(tower::service_fn(move |_| {
+                            let address = address.clone();
+                            async move { connect_tcp(&address, port, Some(bind_address)).await }
+                        }))
+                        .await
                 }
+                None => endpoint.connect().await,
             }
-            Ok(Err(e)) => (ServiceStatus::Down, Some(e.to_string())),
-            Err(_) => (ServiceStatus::Down, Some("HTTPS request timeout".to_string())),
+            .map_err(|e| e.to_string())?;
+            HealthClient::new(channel)
+                .check(request)
+                .await
+                .map(|response| response.into_inner().status)
+                .map_err(|status| format!("gRPC {}: {}", status.code(), status.message()))
+        };
+        
+        match tokio::time::timeout(timeout_duration, check).await {
+            Ok(Ok(status)) if status == ServingStatus::Serving as i32 => (ServiceStatus::Up, None),
+            Ok(Ok(status)) => {
+                let name = ServingStatus::try_from(status)
+                    .map(|s| s.as_str_name())
+                    .unwrap_or("UNRECOGNIZED");
+                (ServiceStatus::Down, Some(format!("Health status {}", name)))
+            }
+            Ok(Err(e)) => (ServiceStatus::Down, Some(e)),
+            Err(_) => (ServiceStatus::Down, Some("gRPC health check timeout".to_string())),
         }
     }
 
-    pub async fn get_statuses(&self) -> HashMap<String, ServiceCheck> {
+    /// The latest published snapshot; cheap to take, as it is shared.
+    pub async fn get_statuses(&self) -> StatusSnapshot {
         self.statuses.read().await.clone()
     }
+
+    /// Writes the current statuses to `path` so the next run can show them
+    /// before its first checks finish.
+    pub async fn save_state(&self, path: &Path) -> Result<()> {
+        let statuses = self.get_sorted_statuses().await;
+        let content = serde_json::to_string(&statuses).context("Failed to serialize statuses")?;
+        
+        // Write then rename, so a crash mid-write can't leave a truncated file
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write state file: {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to replace state file: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Loads statuses saved by `save_state`, marked as restored. Entries for
+    /// services no longer in the config, or disabled there, are dropped. Returns how many were loaded.
+    pub async fn restore_state(&self, path: &Path) -> Result<usize> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state file: {}", path.display()))?;
+        let saved: Vec<ServiceCheck> = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse state file: {}", path.display()))?;
+        
+        let current_keys: HashSet<String> = self
+            .config
+            .hosts
+            .iter()
+            .enumerate()
+            .flat_map(|(h, host)| {
+                host.services
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, service)| service.enabled)
+                    .map(move |(s, service)| ServiceCheck::key_for(h, s, host, service))
+            })
+            .collect();
+        
+        let mut statuses = self.statuses.write().await;
+        let statuses = Arc::make_mut(&mut statuses);
+        let mut restored = 0;
+        for mut check in saved.into_iter().filter(|check| current_keys.contains(&check.key)) {
+            check.restored = true;
+            // Never overwrite a result this run has already produced
+            if !statuses.get(&check.key).is_some_and(|current| current.status != ServiceStatus::Pending) {
+                statuses.insert(check.key.clone(), check);
+                restored += 1;
+            }
+        }
+        self.version.fetch_add(1, Ordering::SeqCst);
+        Ok(restored)
+    }
+
+    /// Saves the state file every `interval` until the task is aborted.
+    pub fn spawn_state_saver(&self, path: PathBuf, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let engine = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = engine.save_state(&path).await {
+                    warn!("Failed to save state: {:#}", e);
+                }
+            }
+        })
+    }
+
+    /// The latest results pulled from each of `settings.peers`.
+    pub async fn get_peer_results(&self) -> PeerSnapshot {
+        self.peer_results.read().await.clone()
+    }
+
+    /// Recent results per service key, oldest first.
+    pub async fn get_samples(&self) -> HashMap<String, VecDeque<CheckSample>> {
+        self.samples.read().await.clone()
+    }
+}
+
+/// Tells connect timeouts apart from slow responses, which reqwest reports alike.
+fn describe_request_error(error: &reqwest::Error, label: &str) -> String {
+    if error.is_connect() && error.is_timeout() {
+        "Connection timeout".to_string()
+    } else if error.is_timeout() {
+        format!("{} read timeout", label)
+    } else {
+        error.to_string()
+    }
+}
+
+fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+    needle.is_empty() || haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+/// Whether the peer still holds a pooled connection open. Stray data from
+/// the server is discarded; end-of-stream or an error means it's gone.
+fn connection_is_open(stream: &TcpStream) -> bool {
+    let mut buffer = [0u8; 256];
+    loop {
+        match stream.try_read(&mut buffer) {
+            Ok(0) => return false,
+            Ok(_) => continue,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return true,
+            Err(_) => return false,
+        }
+    }
+}
+
+/// The URL an `http`/`https` service is checked against, or `None` for other protocols.
+pub fn service_url(address: &str, service: &Service) -> Option<String> {
+    match service.protocol {
+        Protocol::Http => Some(build_url("http", 80, address, service)),
+        Protocol::Https => Some(build_url("https", 443, address, service)),
+        _ => None,
+    }
+}
+
+/// `address:port`, with IPv6 literals bracketed (`[::1]:443`).
+pub fn socket_address(address: &str, port: u16) -> String {
+    format!("{}:{}", url_host(address), port)
+}
+
+/// The address as it appears in a URL or socket address; IPv6 literals
+/// need brackets so their colons aren't read as a port separator.
+fn url_host(address: &str) -> String {
+    if address.parse::<std::net::Ipv6Addr>().is_ok() {
+        format!("[{}]", address)
+    } else {
+        address.to_string()
+    }
+}
+
+fn build_url(scheme: &str, default_port: u16, address: &str, service: &Service) -> String {
+    let url = if service.port == default_port {
+        format!("{}://{}", scheme, url_host(address))
+    } else {
+        format!("{}://{}", scheme, socket_address(address, service.port))
+    };
+    
+    match &service.path {
+        Some(path) => format!("{}{}", url, path),
+        None => url,
+    }
+}
+
+// Longer header values are cut off, like an oversized cookie or CSP
+const MAX_HEADER_VALUE_LEN: usize = 256;
+
+/// The service's `capture_headers` present in `response`, repeated headers
+/// joined with commas.
+fn capture_headers(response: &reqwest::Response, service: &Service) -> Vec<(String, String)> {
+    service
+        .capture_headers
+        .iter()
+        .take(MAX_CAPTURED_HEADERS)
+        .filter_map(|name| {
+            let values: Vec<String> = response
+                .headers()
+                .get_all(name.as_str())
+                .iter()
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .collect();
+            if values.is_empty() {
+                return None;
+            }
+            let value: String = values.join(", ").chars().take(MAX_HEADER_VALUE_LEN).collect();
+            Some((name.clone(), value))
+        })
+        .collect()
+}
+
+/// Opens a TCP connection, from `bind_address` when one is configured.
+pub async fn connect_tcp(address: &str, port: u16, bind_address: Option<IpAddr>) -> std::io::Result<TcpStream> {
+    let Some(local) = bind_address else {
+        return TcpStream::connect((address, port)).await;
+    };
+    
+    // A local address can only reach targets of its own IP family
+    let mut last_error = None;
+    for target in tokio::net::lookup_host((address, port)).await?.filter(|target| target.is_ipv4() == local.is_ipv4()) {
+        let socket = if local.is_ipv4() { TcpSocket::new_v4()? } else { TcpSocket::new_v6()? };
+        socket
+            .bind(SocketAddr::new(local, 0))
+            .map_err(|e| std::io::Error::new(e.kind(), format!("Cannot bind to {}: {}", local, e)))?;
+        match socket.connect(target).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    
+    Err(last_error.unwrap_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::AddrNotAvailable,
+            format!(
+                "{} has no {} address to reach from bind_address {}",
+                address,
+                if local.is_ipv4() { "IPv4" } else { "IPv6" },
+                local
+            ),
+        )
+    }))
+}
+
+fn tls_connector(accept_invalid_certs: bool) -> Result<tokio_native_tls::native_tls::TlsConnector> {
+    Ok(tokio_native_tls::native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(accept_invalid_certs)
+        .build()?)
+}
+
+async fn fetch_cert_expiry(address: &str, port: u16, accept_invalid_certs: bool, bind_address: Option<IpAddr>) -> Result<DateTime<Utc>> {
+    let connector = tokio_native_tls::TlsConnector::from(tls_connector(accept_invalid_certs)?);
+    let stream = connect_tcp(address, port, bind_address).await?;
+    let tls_stream = connector.connect(address, stream).await?;
+    
+    let certificate = tls_stream
+        .get_ref()
+        .peer_certificate()?
+        .ok_or_else(|| anyhow::anyhow!("server sent no certificate"))?;
+    let der = certificate.to_der()?;
+    let (_, parsed) = x509_parser::parse_x509_certificate(&der)
+        .map_err(|e| anyhow::anyhow!("invalid certificate: {}", e))?;
+    
+    DateTime::from_timestamp(parsed.validity().not_after.timestamp(), 0)
+        .ok_or_else(|| anyhow::anyhow!("certificate expiry out of range"))
 }
 
 impl Clone for MonitorEngine {
@@ -248,7 +1968,26 @@ impl Clone for MonitorEngine {
         Self {
             config: self.config.clone(),
             statuses: self.statuses.clone(),
+            samples: self.samples.clone(),
             http_client: self.http_client.clone(),
+            peer_client: self.peer_client.clone(),
+            check_clients: self.check_clients.clone(),
+            connections: self.connections.clone(),
+            resolved: self.resolved.clone(),
+            bind_address: self.bind_address,
+            streaks: self.streaks.clone(),
+            slow_streaks: self.slow_streaks.clone(),
+            last_alerts: self.last_alerts.clone(),
+            flap_held: self.flap_held.clone(),
+            check_limit: self.check_limit.clone(),
+            paused: self.paused.clone(),
+            cycle_running: self.cycle_running.clone(),
+            version: self.version.clone(),
+            last_result_at: self.last_result_at.clone(),
+            history: self.history.clone(),
+            peer_results: self.peer_results.clone(),
+            timings: self.timings.clone(),
+            transitions: self.transitions.clone(),
         }
     }
 } 
\ No newline at end of file
@@ -0,0 +1,52 @@
+use crate::config::Peer;
+use crate::monitor::ServiceCheck;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Latest results pulled from each peer by name, or why the last pull failed.
+pub type PeerSnapshot = Arc<BTreeMap<String, Result<Vec<ServiceCheck>, String>>>;
+
+/// Pulls the peer's own results from its status API, tagged with its name.
+/// Results it pulled from its own peers are left out, so instances that list
+/// each other don't echo results back and forth.
+pub async fn fetch(client: &Client, peer: &Peer, timeout: Duration) -> Result<Vec<ServiceCheck>> {
+    let response = client
+        .get(&peer.url)
+        .timeout(timeout)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to reach {}", peer.url))?;
+    let checks: Vec<ServiceCheck> = response
+        .json()
+        .await
+        .with_context(|| format!("Unexpected response from {}", peer.url))?;
+
+    Ok(checks
+        .into_iter()
+        .filter(|check| check.vantage.is_none())
+        .map(|check| ServiceCheck {
+            vantage: Some(peer.name.clone()),
+            ..check
+        })
+        .collect())
+}
+
+/// The peers' results for the service `check` is for, matched by host and
+/// service name so peers needn't list hosts in the same order.
+pub fn results_for<'a>(peers: &'a PeerSnapshot, check: &ServiceCheck) -> Vec<(&'a str, Option<&'a ServiceCheck>)> {
+    peers
+        .iter()
+        .map(|(name, results)| {
+            let found = results.as_ref().ok().and_then(|checks| {
+                checks
+                    .iter()
+                    .find(|peer_check| peer_check.host_name == check.host_name && peer_check.service_name == check.service_name)
+            });
+            (name.as_str(), found)
+        })
+        .collect()
+}
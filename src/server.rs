@@ -0,0 +1,134 @@
+use anyhow::{Context, Result};
+use std::future::Future;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+const MAX_REQUEST_BYTES: usize = 8192;
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn ok(content_type: &'static str, body: String) -> Self {
+        Self {
+            status: 200,
+            content_type,
+            body,
+        }
+    }
+
+    pub fn not_found() -> Self {
+        Self {
+            status: 404,
+            content_type: "text/plain; charset=utf-8",
+            body: "Not Found\n".to_string(),
+        }
+    }
+
+    pub fn internal_error(message: String) -> Self {
+        Self {
+            status: 500,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{}\n", message),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+/// Binds `0.0.0.0:port` and answers GET requests using `handler`, which
+/// receives the request path. Only what a scraper or curl needs is supported.
+pub async fn serve<F, Fut>(name: &'static str, port: u16, handler: F) -> Result<tokio::task::JoinHandle<()>>
+where
+    F: Fn(String) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = Response> + Send,
+{
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .await
+        .with_context(|| format!("Failed to bind {} server on port {}", name, port))?;
+    info!("Serving {} on port {}", name, port);
+
+    Ok(tokio::spawn(async move {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("Failed to accept {} connection: {}", name, e);
+                    continue;
+                }
+            };
+
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, handler).await {
+                    debug!("{} request from {} failed: {}", name, peer, e);
+                }
+            });
+        }
+    }))
+}
+
+async fn handle_connection<F, Fut>(mut stream: TcpStream, handler: F) -> Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Response>,
+{
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    // Read until the end of the request headers; bodies are ignored
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&buf);
+    let mut parts = request.lines().next().unwrap_or("").split_whitespace();
+
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => {
+            let path = target.split('?').next().unwrap_or(target).to_string();
+            handler(path).await
+        }
+        (Some(_), Some(_)) => Response {
+            status: 405,
+            content_type: "text/plain; charset=utf-8",
+            body: "Method Not Allowed\n".to_string(),
+        },
+        _ => Response {
+            status: 400,
+            content_type: "text/plain; charset=utf-8",
+            body: "Bad Request\n".to_string(),
+        },
+    };
+
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.reason(),
+        response.content_type,
+        response.body.len()
+    );
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
@@ -0,0 +1,307 @@
+use crate::config::Protocol;
+use crate::monitor::ServiceStatus;
+use ratatui::style::{Color, Style};
+use std::time::Duration;
+use tracing::warn;
+
+/// Colors used across the dashboard, selected by `settings.theme`.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub accent: Color,
+    pub text: Color,
+    pub muted: Color,
+    pub info: Color,
+    pub key: Color,
+    pub warning: Color,
+    pub selected_fg: Color,
+    pub selected_bg: Color,
+    pub up: Color,
+    pub degraded: Color,
+    pub down: Color,
+    pub unknown: Color,
+    pub pending: Color,
+    pub flapping: Color,
+    pub maintenance: Color,
+}
+
+impl Theme {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "default" => Self::default_theme(),
+            "dark" => Self::dark(),
+            "light" => Self::light(),
+            "high-contrast" | "high_contrast" | "highcontrast" => Self::high_contrast(),
+            other => {
+                warn!("Unknown theme '{}', falling back to default", other);
+                Self::default_theme()
+            }
+        }
+    }
+
+    pub fn status_color(&self, status: &ServiceStatus) -> Color {
+        match status {
+            ServiceStatus::Up => self.up,
+            ServiceStatus::Degraded => self.degraded,
+            ServiceStatus::Down => self.down,
+            ServiceStatus::Unknown => self.unknown,
+            ServiceStatus::Pending => self.pending,
+            ServiceStatus::Maintenance => self.maintenance,
+        }
+    }
+
+    fn default_theme() -> Self {
+        Self {
+            accent: Color::Cyan,
+            text: Color::White,
+            muted: Color::Gray,
+            info: Color::Blue,
+            key: Color::Yellow,
+            warning: Color::Yellow,
+            selected_fg: Color::Black,
+            selected_bg: Color::Cyan,
+            up: Color::Green,
+            degraded: Color::LightRed,
+            down: Color::Red,
+            unknown: Color::Yellow,
+            pending: Color::Gray,
+            flapping: Color::Magenta,
+            maintenance: Color::Blue,
+        }
+    }
+
+    fn dark() -> Self {
+        Self {
+            accent: Color::LightCyan,
+            text: Color::White,
+            muted: Color::DarkGray,
+            info: Color::LightBlue,
+            key: Color::LightYellow,
+            warning: Color::LightYellow,
+            selected_fg: Color::Black,
+            selected_bg: Color::LightCyan,
+            up: Color::LightGreen,
+            degraded: Color::LightMagenta,
+            down: Color::LightRed,
+            unknown: Color::LightYellow,
+            pending: Color::DarkGray,
+            flapping: Color::Magenta,
+            maintenance: Color::LightBlue,
+        }
+    }
+
+    // Avoids cyan and yellow, which wash out on light backgrounds
+    fn light() -> Self {
+        Self {
+            accent: Color::Blue,
+            text: Color::Black,
+            muted: Color::DarkGray,
+            info: Color::Blue,
+            key: Color::Magenta,
+            warning: Color::Rgb(175, 95, 0),
+            selected_fg: Color::White,
+            selected_bg: Color::Blue,
+            up: Color::Rgb(0, 135, 0),
+            degraded: Color::Magenta,
+            down: Color::Red,
+            unknown: Color::Rgb(175, 95, 0),
+            pending: Color::DarkGray,
+            flapping: Color::Rgb(135, 0, 175),
+            maintenance: Color::Rgb(0, 95, 175),
+        }
+    }
+
+    fn high_contrast() -> Self {
+        Self {
+            accent: Color::White,
+            text: Color::White,
+            muted: Color::White,
+            info: Color::White,
+            key: Color::LightYellow,
+            warning: Color::LightYellow,
+            selected_fg: Color::Black,
+            selected_bg: Color::White,
+            up: Color::LightGreen,
+            degraded: Color::LightMagenta,
+            down: Color::LightRed,
+            unknown: Color::LightYellow,
+            pending: Color::White,
+            flapping: Color::LightCyan,
+            maintenance: Color::LightBlue,
+        }
+    }
+}
+
+/// How much of a failing service's row takes its status color, selected by
+/// `settings.row_highlight`. Full backgrounds are hard to miss but too loud
+/// in some terminals, so text-only coloring is available too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RowHighlight {
+    #[default]
+    None,
+    Text,
+    Background,
+}
+
+impl RowHighlight {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "none" => RowHighlight::None,
+            "text" => RowHighlight::Text,
+            "background" => RowHighlight::Background,
+            other => {
+                warn!("Unknown row highlight '{}', falling back to none", other);
+                RowHighlight::None
+            }
+        }
+    }
+
+    /// Row style for a service with `status`; only down and degraded rows
+    /// are highlighted.
+    pub fn style(self, status: &ServiceStatus, theme: &Theme) -> Style {
+        if !matches!(status, ServiceStatus::Down | ServiceStatus::Degraded) {
+            return Style::default();
+        }
+        let color = theme.status_color(status);
+        match self {
+            RowHighlight::None => Style::default(),
+            RowHighlight::Text => Style::default().fg(color),
+            RowHighlight::Background => Style::default().fg(theme.selected_fg).bg(color),
+        }
+    }
+}
+
+/// How statuses are drawn, selected by `settings.status_style`. Emoji render
+/// badly in many terminals and over SSH, so plain ASCII is available too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusStyle {
+    #[default]
+    Emoji,
+    Ascii,
+    NerdFont,
+}
+
+impl StatusStyle {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "emoji" => StatusStyle::Emoji,
+            "ascii" => StatusStyle::Ascii,
+            "nerdfont" | "nerd-font" => StatusStyle::NerdFont,
+            other => {
+                warn!("Unknown status style '{}', falling back to emoji", other);
+                StatusStyle::Emoji
+            }
+        }
+    }
+
+    pub fn glyph(self, status: &ServiceStatus) -> &'static str {
+        match (self, status) {
+            (StatusStyle::Emoji, ServiceStatus::Up) => "🟢",
+            (StatusStyle::Emoji, ServiceStatus::Degraded) => "🟠",
+            (StatusStyle::Emoji, ServiceStatus::Down) => "🔴",
+            (StatusStyle::Emoji, ServiceStatus::Unknown) => "🟡",
+            (StatusStyle::Emoji, ServiceStatus::Pending) => "⏳",
+            (StatusStyle::Emoji, ServiceStatus::Maintenance) => "🔧",
+            (StatusStyle::Ascii, ServiceStatus::Up) => "[UP]",
+            (StatusStyle::Ascii, ServiceStatus::Degraded) => "[DG]",
+            (StatusStyle::Ascii, ServiceStatus::Down) => "[DN]",
+            (StatusStyle::Ascii, ServiceStatus::Unknown) => "[??]",
+            (StatusStyle::Ascii, ServiceStatus::Pending) => "[..]",
+            (StatusStyle::Ascii, ServiceStatus::Maintenance) => "[MT]",
+            (StatusStyle::NerdFont, ServiceStatus::Up) => "\u{f058}",
+            (StatusStyle::NerdFont, ServiceStatus::Degraded) => "\u{f071}",
+            (StatusStyle::NerdFont, ServiceStatus::Down) => "\u{f057}",
+            (StatusStyle::NerdFont, ServiceStatus::Unknown) => "\u{f059}",
+            (StatusStyle::NerdFont, ServiceStatus::Pending) => "\u{f254}",
+            (StatusStyle::NerdFont, ServiceStatus::Maintenance) => "\u{f0ad}",
+        }
+    }
+
+    pub fn flapping_glyph(self) -> &'static str {
+        match self {
+            StatusStyle::Emoji => "〰",
+            StatusStyle::Ascii => "[~~]",
+            StatusStyle::NerdFont => "\u{f074}",
+        }
+    }
+
+    /// Default icon for services of a protocol. ASCII has none, as letters
+    /// would only repeat the protocol column.
+    pub fn protocol_icon(self, protocol: &Protocol) -> &'static str {
+        match (self, protocol) {
+            (StatusStyle::Ascii, _) => "",
+            (StatusStyle::Emoji, Protocol::Tcp) => "🔌",
+            (StatusStyle::Emoji, Protocol::Udp) => "📡",
+            (StatusStyle::Emoji, Protocol::Http) => "🌐",
+            (StatusStyle::Emoji, Protocol::Https) => "🔒",
+            (StatusStyle::Emoji, Protocol::Dns) => "📖",
+            (StatusStyle::Emoji, Protocol::Grpc) => "🧩",
+            (StatusStyle::Emoji, Protocol::Banner) => "💬",
+            (StatusStyle::Emoji, Protocol::Ws | Protocol::Wss) => "🔁",
+            (StatusStyle::Emoji, Protocol::Unix) => "📁",
+            (StatusStyle::NerdFont, Protocol::Tcp) => "\u{f0c1}",
+            (StatusStyle::NerdFont, Protocol::Udp) => "\u{f1eb}",
+            (StatusStyle::NerdFont, Protocol::Http) => "\u{f0ac}",
+            (StatusStyle::NerdFont, Protocol::Https) => "\u{f023}",
+            (StatusStyle::NerdFont, Protocol::Dns) => "\u{f02d}",
+            (StatusStyle::NerdFont, Protocol::Grpc) => "\u{f1e6}",
+            (StatusStyle::NerdFont, Protocol::Banner) => "\u{f075}",
+            (StatusStyle::NerdFont, Protocol::Ws | Protocol::Wss) => "\u{f0ec}",
+            (StatusStyle::NerdFont, Protocol::Unix) => "\u{f07b}",
+        }
+    }
+
+    /// Glyph followed by the status name, e.g. `[DN] DOWN`.
+    pub fn label(self, status: &ServiceStatus) -> String {
+        format!("{} {}", self.glyph(status), status.as_str().to_uppercase())
+    }
+}
+
+/// How numbers are written, from `settings.response_time_decimals` and
+/// `settings.thousands_separator`.
+#[derive(Debug, Clone, Default)]
+pub struct NumberFormat {
+    pub decimals: Option<usize>,
+    pub thousands_separator: String,
+}
+
+impl NumberFormat {
+    /// A count with its digits grouped in threes, e.g. `12,345`.
+    pub fn count(&self, count: usize) -> String {
+        let digits = count.to_string();
+        let mut grouped = String::new();
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                grouped.push_str(&self.thousands_separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    /// A response time at a scale that keeps sub-millisecond checks readable.
+    /// Extra digits are cut off rather than rounded, so 999.6ms never shows as 1000ms.
+    pub fn response_time(&self, duration: Duration) -> String {
+        let (unit_nanos, unit, default_decimals) = if duration < Duration::from_millis(1) {
+            (1_000, "µs", 0)
+        } else if duration < Duration::from_secs(1) {
+            (1_000_000, "ms", 0)
+        } else {
+            (1_000_000_000, "s", 2)
+        };
+        // Nanoseconds are as fine as a Duration gets
+        let decimals = self.decimals.unwrap_or(default_decimals).min(9);
+        let nanos = duration.as_nanos();
+        let whole = nanos / unit_nanos;
+        if decimals == 0 {
+            return format!("{}{}", whole, unit);
+        }
+        let fraction = (nanos % unit_nanos) * 10u128.pow(decimals as u32) / unit_nanos;
+        format!("{}.{:0width$}{}", whole, fraction, unit, width = decimals)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::default_theme()
+    }
+}
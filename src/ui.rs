@@ -1,23 +1,69 @@
-use crate::app::App;
+use crate::app::{App, SelectedItem, SortMode, ViewMode};
+use crate::config::Protocol;
+use crate::keymap::{self, Action, KeyContext, KEY_BINDINGS};
+use crate::monitor::{ServiceCheck, ServiceStatus};
+use crate::theme::Theme;
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEvent, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    symbols,
     text::{Span, Line},
     widgets::{
-        Block, Borders, Cell, Paragraph, Row, Table, Wrap,
+        Axis, Block, Borders, Cell, Chart, Dataset, GraphType, Paragraph, Row, Table, TableState, Wrap,
     },
     Frame, Terminal,
 };
-use std::io;
+use std::io::{self, Write};
+use std::rc::Rc;
 use tokio::time::{Duration, Instant};
 
+// Two clicks on the same row within this window count as a double-click
+const DOUBLE_CLICK_WINDOW: Duration = Duration::from_millis(400);
+
+// The title and status bar show a clock with seconds
+const CLOCK_REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+// Lines moved per PageUp/PageDown in the detail views' error panel
+const DETAIL_SCROLL_STEP: i32 = 5;
+
+// Changes moved per PageUp/PageDown in the events log
+const EVENTS_SCROLL_STEP: i32 = 10;
+
+// The services table starts with a border line and a header row
+const TABLE_HEADER_ROWS: u16 = 2;
+
+// Column widths shared by the services table and the triage list
+const SERVICE_COLUMNS: [Constraint; 6] = [
+    Constraint::Length(25),  // Host/Service - increased
+    Constraint::Length(8),   // Port - kept same
+    Constraint::Length(10),  // Protocol - kept same
+    Constraint::Length(14),  // Status - fits ascii labels
+    Constraint::Length(15),  // Response Time - kept same
+    Constraint::Min(20),     // Details - much more space, minimum 20 chars
+];
+
+// The flat view splits the first column into host and service
+const FLAT_SERVICE_COLUMNS: [Constraint; 7] = [
+    Constraint::Length(16),  // Host
+    Constraint::Length(20),  // Service
+    Constraint::Length(8),   // Port
+    Constraint::Length(10),  // Protocol
+    Constraint::Length(14),  // Status
+    Constraint::Length(15),  // Response Time
+    Constraint::Min(20),     // Details
+];
+
+// Below this size the fixed-height panels leave no room for content
+const MIN_WIDTH: u16 = 80;
+const MIN_HEIGHT: u16 = 20;
+
 pub async fn run_app(mut app: App) -> Result<()> {
     // Terminal initialization
     enable_raw_mode()?;
@@ -49,58 +95,189 @@ async fn run_app_internal<B: Backend>(
     app: &mut App,
 ) -> Result<()> {
     let mut last_tick = Instant::now();
-    let tick_rate = Duration::from_millis(250);
+    let tick_rate = Duration::from_millis(app.config.settings.ui_tick_ms);
+    let mut last_click: Option<(Instant, usize)> = None;
+    let mut needs_redraw = true;
+    let mut last_draw = Instant::now();
 
     loop {
-        terminal.draw(|f| ui(f, app))?;
+        // Redraw on input or new results, and once a second to keep the clock moving
+        if needs_redraw || last_draw.elapsed() >= CLOCK_REDRAW_INTERVAL {
+            terminal.draw(|f| ui(f, app))?;
+            needs_redraw = false;
+            last_draw = Instant::now();
+        }
 
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
 
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
-                        return Ok(());
-                    }
-                    KeyCode::Char('h') => {
-                        app.toggle_help();
-                    }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        app.next_item();
-                    }
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        app.previous_item();
-                    }
-                    KeyCode::Char('r') => {
-                        // Trigger manual refresh
-                        app.update_statuses().await;
+            needs_redraw = true;
+            match event::read()? {
+                Event::Mouse(mouse) => {
+                    handle_mouse(app, mouse, terminal.size()?, &mut last_click);
+                }
+                Event::Resize(_, _) => {
+                    // Pick up the new size now so the redraw below uses it
+                    terminal.autoresize()?;
+                }
+                Event::Key(key) => {
+                    if app.filter_editing {
+                        match key.code {
+                            KeyCode::Esc => app.clear_filter(),
+                            KeyCode::Enter => app.confirm_filter(),
+                            KeyCode::Backspace => app.pop_filter_char(),
+                            KeyCode::Char(c) => app.push_filter_char(c),
+                            _ => {}
+                        }
+                        continue;
                     }
-                    KeyCode::Enter => {
-                        if !app.show_help {
-                            app.enter_host_detail();
+                    if app.note_target.is_some() {
+                        match key.code {
+                            KeyCode::Esc => app.cancel_note(),
+                            KeyCode::Enter => app.confirm_note(),
+                            KeyCode::Backspace => app.pop_note_char(),
+                            KeyCode::Char(c) => app.push_note_char(c),
+                            _ => {}
                         }
+                        continue;
                     }
-                    KeyCode::Char('b') | KeyCode::Char('B') => {
-                        if app.show_host_detail {
-                            app.exit_host_detail();
+
+                    if let Some(action) = keymap::action_for(app, key.code) {
+                        if handle_action(terminal, app, action, key.code).await? {
+                            return Ok(());
                         }
                     }
-                    _ => {}
                 }
+                _ => {}
             }
         }
 
         if last_tick.elapsed() >= tick_rate {
-            app.update_statuses().await;
+            if app.tick().await {
+                needs_redraw = true;
+            }
+            if app.take_bell() {
+                ring_bell()?;
+            }
             last_tick = Instant::now();
         }
     }
 }
 
-fn ui(f: &mut Frame, app: &App) {
-    let chunks = Layout::default()
+/// Carries out a key binding's action. Returns whether the app should quit.
+async fn handle_action<B: Backend>(terminal: &mut Terminal<B>, app: &mut App, action: Action, code: KeyCode) -> Result<bool> {
+    match action {
+        Action::Quit => return Ok(true),
+        Action::ToggleHelp => app.toggle_help(),
+        Action::ScrollHelpUp => app.scroll_help(-1),
+        Action::ScrollHelpDown => app.scroll_help(1),
+        Action::ClearFilter => app.clear_filter(),
+        Action::NextItem => app.next_item(),
+        Action::PreviousItem => app.previous_item(),
+        Action::StartFilter => app.start_filter(),
+        Action::CycleSort => app.cycle_sort_mode(),
+        Action::ToggleTriage => app.toggle_triage(),
+        Action::CycleTagFilter => app.cycle_tag_filter(),
+        Action::Refresh => app.manual_refresh().await,
+        Action::RefreshSelected => app.refresh_selected(),
+        Action::TogglePause => app.toggle_pause(),
+        Action::ToggleEnabled => app.toggle_enabled().await,
+        Action::Acknowledge => app.toggle_acknowledged(),
+        Action::OpenUrl => {
+            if let Some(url) = app.selected_service_url() {
+                match open_in_browser(terminal, &url) {
+                    Ok(()) => app.set_status_message(format!("Opened {}", url)),
+                    Err(e) => app.set_status_message(format!("Failed to open {}: {}", url, e)),
+                }
+            }
+        }
+        Action::SaveSnapshot => app.save_snapshot(),
+        Action::EditNote => app.start_note(),
+        Action::EnterDetail => app.enter_detail(),
+        Action::ToggleCollapse => app.toggle_collapse(),
+        Action::ExitDetail => app.exit_detail(),
+        Action::ScrollDetailUp => app.scroll_detail(-DETAIL_SCROLL_STEP),
+        Action::ScrollDetailDown => app.scroll_detail(DETAIL_SCROLL_STEP),
+        Action::ToggleGraph => app.toggle_graph(),
+        Action::ToggleEvents => app.toggle_events(),
+        Action::ScrollEventsUp => app.scroll_events(-1),
+        Action::ScrollEventsDown => app.scroll_events(1),
+        Action::PageEventsUp => app.scroll_events(-EVENTS_SCROLL_STEP),
+        Action::PageEventsDown => app.scroll_events(EVENTS_SCROLL_STEP),
+        Action::JumpToHost => {
+            if let KeyCode::Char(c) = code {
+                app.jump_to_host(c);
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn ring_bell() -> Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x07")?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Launches the default browser with the terminal restored to normal mode,
+/// in case the launcher (or a terminal browser) needs it.
+fn open_in_browser<B: Backend>(terminal: &mut Terminal<B>, url: &str) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    
+    let opened = open::that(url);
+    
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+    
+    opened.map_err(Into::into)
+}
+
+fn handle_mouse(app: &mut App, mouse: MouseEvent, size: Rect, last_click: &mut Option<(Instant, usize)>) {
+    // Mouse selection only applies to the main services table
+    if app.show_help || app.in_overlay() || app.in_detail_view() || app.show_triage || app.filter_editing || app.note_target.is_some() || too_small(size) {
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::ScrollDown => app.next_item(),
+        MouseEventKind::ScrollUp => app.previous_item(),
+        MouseEventKind::Down(MouseButton::Left) => {
+            let table_area = screen_layout(size)[2];
+            let first_row = table_area.y + TABLE_HEADER_ROWS;
+            if mouse.row < first_row || mouse.row >= table_area.bottom().saturating_sub(1) {
+                return;
+            }
+
+            // Account for how far the table has been scrolled
+            let row = app.table_state.offset() + (mouse.row - first_row) as usize;
+            if row >= app.get_total_items() {
+                return;
+            }
+            app.selected_index = row;
+            app.sync_table_selection();
+
+            let is_double_click = matches!(
+                last_click,
+                Some((at, index)) if *index == row && at.elapsed() <= DOUBLE_CLICK_WINDOW
+            );
+            if is_double_click {
+                *last_click = None;
+                app.enter_detail();
+            } else {
+                *last_click = Some((Instant::now(), row));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn screen_layout(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
         .direction(Direction::Vertical)
         .margin(2)
         .constraints(
@@ -112,15 +289,45 @@ fn ui(f: &mut Frame, app: &App) {
             ]
             .as_ref(),
         )
-        .split(f.size());
+        .split(area)
+}
+
+fn too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+fn ui(f: &mut Frame, app: &mut App) {
+    let area = f.size();
+    if too_small(area) {
+        render_too_small(f, &app.theme, area);
+        return;
+    }
+
+    let chunks = screen_layout(area);
+
+    if app.in_overlay() && !app.show_help {
+        // Takes over everything above the status bar
+        let overlay_area = Rect { height: chunks[3].y - chunks[0].y, ..chunks[0] };
+        if app.show_graph {
+            render_graph(f, app, overlay_area);
+        } else {
+            render_events(f, app, overlay_area);
+        }
+        render_status_bar(f, app, chunks[3]);
+        return;
+    }
 
     render_title(f, app, chunks[0]);
     render_stats(f, app, chunks[1]);
     
     if app.show_help {
-        render_help(f, chunks[2]);
+        render_help(f, app, chunks[2]);
+    } else if app.show_service_detail {
+        render_service_detail(f, app, chunks[2]);
     } else if app.show_host_detail {
         render_host_detail(f, app, chunks[2]);
+    } else if app.show_triage {
+        render_triage_table(f, app, chunks[2]);
     } else {
         render_services_table(f, app, chunks[2]);
     }
@@ -128,6 +335,23 @@ fn ui(f: &mut Frame, app: &App) {
     render_status_bar(f, app, chunks[3]);
 }
 
+fn render_too_small(f: &mut Frame, theme: &Theme, area: Rect) {
+    let message = vec![
+        Line::styled("Terminal too small", Style::default().fg(theme.down).add_modifier(Modifier::BOLD)),
+        Line::styled(
+            format!("{}x{}, need at least {}x{}", area.width, area.height, MIN_WIDTH, MIN_HEIGHT),
+            Style::default().fg(theme.text),
+        ),
+    ];
+    // Center vertically; the message wraps if even that is too narrow
+    let top = area.height.saturating_sub(message.len() as u16) / 2;
+    let message_area = Rect { y: area.y + top, height: area.height - top, ..area };
+    let paragraph = Paragraph::new(message)
+        .alignment(ratatui::layout::Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, message_area);
+}
+
 fn render_title(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let now = chrono::Utc::now();
     let timezone = &app.config.settings.timezone;
@@ -143,24 +367,47 @@ fn render_title(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         Err(_) => app.last_update.format("%H:%M:%S"),
     };
     
-    let clock_text = format!("🕐 {} | Last Update: {}", 
+    let mut clock_text = format!("🕐 {} | Last Update: {}", 
         formatted_time,
         last_update_formatted);
+    if let Some(availability) = app.fleet_availability() {
+        clock_text = format!("Fleet availability: {:.1}% | {}", availability, clock_text);
+    }
+    let stale_age = app.stale_data_age();
+    // Recomputed every draw, so it keeps counting up while nothing arrives
+    let update_age_style = if stale_age.is_some() {
+        Style::default().fg(app.theme.down).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(app.theme.muted)
+    };
+    
+    let mut heading = vec![Span::styled(
+        "DAYSTROM TUI MONITORING DASHBOARD",
+        Style::default()
+            .fg(app.theme.accent)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if app.paused {
+        heading.push(Span::styled(
+            "  ⏸ PAUSED",
+            Style::default().fg(app.theme.warning).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(age) = stale_age {
+        heading.push(Span::styled(
+            format!("  ⚠ STALE DATA ({}s old)", age.as_secs()),
+            Style::default().fg(app.theme.down).add_modifier(Modifier::BOLD),
+        ));
+    }
     
     let title = Paragraph::new(vec![
-        Line::from(vec![
-            Span::styled(
-                "DAYSTROM TUI MONITORING DASHBOARD",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ]),
+        Line::from(heading),
         Line::from(vec![
             Span::styled(
                 clock_text,
-                Style::default().fg(Color::Gray),
+                Style::default().fg(app.theme.muted),
             ),
+            Span::styled(format!(" (updated {} ago)", format_elapsed(app.last_update)), update_age_style),
         ]),
     ])
     .block(Block::default().borders(Borders::ALL).style(Style::default()))
@@ -170,35 +417,66 @@ fn render_title(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
 }
 
 fn render_stats(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let (up, down, unknown) = app.get_summary_stats();
+    let stats = app.get_summary_stats();
+    let theme = &app.theme;
     let total = app.get_total_services();
     let hosts = app.get_host_count();
+    let host_summary = app.get_host_summary();
+    let count = |n: usize| app.number_format.count(n);
 
-    let stats_text = vec![
-        Line::from(vec![
-            Span::styled("🟢 UP: ", Style::default().fg(Color::Green)),
-            Span::styled(format!("{}", up), Style::default().fg(Color::Green)),
-            Span::styled("  ", Style::default()),
-            Span::styled("🔴 DOWN: ", Style::default().fg(Color::Red)),
-            Span::styled(format!("{}", down), Style::default().fg(Color::Red)),
+    let mut status_counts = vec![
+        Span::styled(format!("{} UP: ", app.status_style.glyph(&ServiceStatus::Up)), Style::default().fg(theme.up)),
+        Span::styled(count(stats.up), Style::default().fg(theme.up)),
+        Span::styled("  ", Style::default()),
+        Span::styled(format!("{} DEGRADED: ", app.status_style.glyph(&ServiceStatus::Degraded)), Style::default().fg(theme.degraded)),
+        Span::styled(count(stats.degraded), Style::default().fg(theme.degraded)),
+        Span::styled("  ", Style::default()),
+        Span::styled(format!("{} DOWN: ", app.status_style.glyph(&ServiceStatus::Down)), Style::default().fg(theme.down)),
+        Span::styled(count(stats.down), Style::default().fg(theme.down)),
+        Span::styled("  ", Style::default()),
+        Span::styled(format!("{} UNKNOWN: ", app.status_style.glyph(&ServiceStatus::Unknown)), Style::default().fg(theme.unknown)),
+        Span::styled(count(stats.unknown), Style::default().fg(theme.unknown)),
+        Span::styled("  ", Style::default()),
+    ];
+    // Only worth the room until the first checks finish
+    if stats.pending > 0 {
+        status_counts.extend([
+            Span::styled(format!("{} PENDING: ", app.status_style.glyph(&ServiceStatus::Pending)), Style::default().fg(theme.pending)),
+            Span::styled(count(stats.pending), Style::default().fg(theme.pending)),
             Span::styled("  ", Style::default()),
-            Span::styled("🟡 UNKNOWN: ", Style::default().fg(Color::Yellow)),
-            Span::styled(format!("{}", unknown), Style::default().fg(Color::Yellow)),
-        ]),
+        ]);
+    }
+    status_counts.extend([
+        Span::styled(format!("{} MAINT: ", app.status_style.glyph(&ServiceStatus::Maintenance)), Style::default().fg(theme.maintenance)),
+        Span::styled(count(stats.maintenance), Style::default().fg(theme.maintenance)),
+        Span::styled("  ", Style::default()),
+        Span::styled("ACKED: ", Style::default().fg(theme.muted)),
+        Span::styled(count(stats.acknowledged), Style::default().fg(theme.muted)),
+    ]);
+
+    let mut stats_text = vec![
+        Line::from(status_counts),
         Line::from(vec![
-            Span::styled("Total Services: ", Style::default().fg(Color::Blue)),
-            Span::styled(format!("{}", total), Style::default().fg(Color::Blue)),
+            Span::styled("Total Services: ", Style::default().fg(theme.info)),
+            Span::styled(count(total), Style::default().fg(theme.info)),
             Span::styled("  ", Style::default()),
-            Span::styled("Hosts: ", Style::default().fg(Color::Blue)),
-            Span::styled(format!("{}", hosts), Style::default().fg(Color::Blue)),
+            Span::styled("Hosts: ", Style::default().fg(theme.info)),
+            Span::styled(format!("{} (", count(hosts)), Style::default().fg(theme.info)),
+            Span::styled(format!("{} healthy", count(host_summary.healthy)), Style::default().fg(theme.up)),
+            Span::styled(" / ", Style::default().fg(theme.info)),
+            Span::styled(format!("{} degraded", count(host_summary.degraded)), Style::default().fg(theme.degraded)),
+            Span::styled(" / ", Style::default().fg(theme.info)),
+            Span::styled(format!("{} down", count(host_summary.down)), Style::default().fg(theme.down)),
+            Span::styled(")", Style::default().fg(theme.info)),
             Span::styled("  ", Style::default()),
-            Span::styled("Refresh: ", Style::default().fg(Color::Blue)),
+            Span::styled("Refresh: ", Style::default().fg(theme.info)),
             Span::styled(
                 format!("{}s", app.get_refresh_interval().as_secs()),
-                Style::default().fg(Color::Blue),
+                Style::default().fg(theme.info),
             ),
         ]),
     ];
+    stats_text[1].spans.extend(timing_spans(app));
 
     let stats = Paragraph::new(stats_text)
         .block(Block::default().borders(Borders::ALL).title("Statistics"))
@@ -207,134 +485,471 @@ fn render_stats(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     f.render_widget(stats, area);
 }
 
-fn render_services_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+/// Last full cycle time and slowest check, flagged once the slowest check
+/// takes most of its interval.
+fn timing_spans<'a>(app: &App) -> Vec<Span<'a>> {
+    let theme = &app.theme;
+    let mut spans = Vec::new();
+    if let Some(cycle) = app.check_timing.last_cycle {
+        spans.extend([
+            Span::styled("  Cycle: ", Style::default().fg(theme.info)),
+            Span::styled(app.number_format.response_time(cycle), Style::default().fg(theme.info)),
+        ]);
+    }
+    if let Some((key, duration, interval)) = &app.check_timing.slowest {
+        let name = app
+            .statuses
+            .get(key)
+            .map(|check| format!("{}/{}", check.host_name, check.service_name))
+            .unwrap_or_else(|| key.clone());
+        let style = if app.falling_behind() {
+            Style::default().fg(theme.warning).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.info)
+        };
+        spans.extend([
+            Span::styled("  Slowest: ", Style::default().fg(theme.info)),
+            Span::styled(
+                format!("{} {} of {}s", name, app.number_format.response_time(*duration), interval.as_secs()),
+                style,
+            ),
+        ]);
+    }
+    spans
+}
+
+/// A service's row in the services table, after the `leading` name cells.
+fn service_row<'a>(app: &App, service: &'a ServiceCheck, leading: Vec<Cell<'a>>, selected: bool) -> Row<'a> {
+    let response_time = service.response_time.map(|time| app.number_format.response_time(time)).unwrap_or_else(|| "N/A".to_string());
+    let error_msg = service.error_message.as_deref().or(service.detail.as_deref()).unwrap_or("");
+    let acknowledged = app.is_acknowledged(&service.key);
+
+    let mut cells = leading;
+    cells.extend([
+        Cell::from(port_label(service)),
+        Cell::from(format!("{}", service.protocol)),
+        status_cell(app, service, acknowledged),
+        Cell::from(response_time),
+        Cell::from(error_msg),
+    ]);
+    Row::new(cells).style(if selected {
+        Style::default().fg(app.theme.selected_fg).bg(app.theme.selected_bg)
+    } else if acknowledged || service.disabled {
+        Style::default().fg(app.theme.muted).add_modifier(Modifier::DIM)
+    } else {
+        app.row_highlight.style(&service.status, &app.theme)
+    })
+}
+
+/// The services table without host headers, for `view_mode: flat`.
+fn render_flat_services_table(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let services: Vec<ServiceCheck> = app
+        .visible_rows()
+        .into_iter()
+        .filter_map(|item| match item {
+            SelectedItem::Service(service) => Some(*service),
+            SelectedItem::HostHeader(_) => None,
+        })
+        .collect();
+
+    let rows: Vec<Row> = services
+        .iter()
+        .enumerate()
+        .map(|(index, service)| {
+            let name = match app.service_icon(service) {
+                "" => service.service_name.clone(),
+                icon => format!("{} {}", icon, service.service_name),
+            };
+            service_row(app, service, vec![Cell::from(service.host_name.as_str()), Cell::from(name)], index == app.selected_index)
+        })
+        .collect();
+
+    let table = Table::new(rows, FLAT_SERVICE_COLUMNS)
+    .header(
+        Row::new(vec![
+            sort_header("Host", app.sort_mode == SortMode::Name, "▲"),
+            "Service".to_string(),
+            "Port".to_string(),
+            "Protocol".to_string(),
+            sort_header("Status", app.sort_mode == SortMode::Status, "▼"),
+            sort_header("Response Time", app.sort_mode == SortMode::ResponseTime, "▼"),
+            "Details".to_string(),
+        ])
+        .style(Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(format!("Services (sorted by {})", app.sort_mode)))
+    .column_spacing(1);
+
+    f.render_stateful_widget(table, area, &mut app.table_state);
+}
+
+fn render_services_table(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
     let grouped = app.get_grouped_status_list();
     
     if grouped.is_empty() {
-        let no_data = Paragraph::new("No services configured or no data available yet...")
+        let message = if app.is_filter_active() || app.tag_filter.is_some() {
+            "No services match the current filter..."
+        } else {
+            "No services configured or no data available yet..."
+        };
+        let no_data = Paragraph::new(message)
             .block(Block::default().borders(Borders::ALL).title("Services"))
             .alignment(ratatui::layout::Alignment::Center);
         f.render_widget(no_data, area);
         return;
     }
 
+    if app.view_mode == ViewMode::Flat {
+        render_flat_services_table(f, app, area);
+        return;
+    }
+
+    let selected_style = Style::default().fg(app.theme.selected_fg).bg(app.theme.selected_bg);
     let mut rows: Vec<Row> = Vec::new();
-    let mut host_index = 0;
-    
     for (host_name, services) in &grouped {
-        // Add host header row - only host headers are selectable
-        let is_host_selected = host_index == app.selected_index;
+        let collapsed = app.is_collapsed(host_name);
+        
+        // Collapsed hosts summarise their services on the header line instead
+        let summary = if collapsed {
+            let up = services.iter().filter(|s| s.status == ServiceStatus::Up).count();
+            format!("{}/{} up", up, services.len())
+        } else {
+            String::new()
+        };
+        
+        let is_host_selected = rows.len() == app.selected_index;
         let host_header = Row::new(vec![
-            Cell::from(format!("{}", host_name)),
-            Cell::from(""),
+            Cell::from(Line::from(vec![
+                Span::styled("● ", Style::default().fg(app.theme.status_color(&app.get_host_rollup(host_name)))),
+                Span::raw(format!("{} {}", if collapsed { "▸" } else { "▾" }, host_name)),
+            ])),
             Cell::from(""),
             Cell::from(""),
+            Cell::from(summary),
             Cell::from(""),
             Cell::from(""),
         ])
         .style(if is_host_selected {
-            Style::default().fg(Color::Black).bg(Color::Cyan)
+            selected_style
         } else {
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD)
         });
         rows.push(host_header);
-        host_index += 1;
         
-        // Add service rows - these are not selectable, just display
+        if collapsed {
+            continue;
+        }
+        
         for service in services {
-            let _status_color = match service.status {
-                crate::monitor::ServiceStatus::Up => Color::Green,
-                crate::monitor::ServiceStatus::Down => Color::Red,
-                crate::monitor::ServiceStatus::Unknown => Color::Yellow,
+            let is_service_selected = rows.len() == app.selected_index;
+            let name = match app.service_icon(service) {
+                "" => format!("  └─ {}", service.service_name),
+                icon => format!("  └─ {} {}", icon, service.service_name),
             };
+            rows.push(service_row(app, service, vec![Cell::from(name)], is_service_selected));
+        }
+    }
 
-            let response_time = if service.response_time.as_millis() > 0 {
-                format!("{}ms", service.response_time.as_millis())
-            } else {
-                "N/A".to_string()
-            };
+    let table = Table::new(rows, SERVICE_COLUMNS)
+    .header(
+        Row::new(vec![
+            sort_header("Host/Service", app.sort_mode == SortMode::Name, "▲"),
+            "Port".to_string(),
+            "Protocol".to_string(),
+            sort_header("Status", app.sort_mode == SortMode::Status, "▼"),
+            sort_header("Response Time", app.sort_mode == SortMode::ResponseTime, "▼"),
+            "Details".to_string(),
+        ])
+        .style(Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(format!("Services (sorted by {})", app.sort_mode)))
+    .column_spacing(1);
+
+    f.render_stateful_widget(table, area, &mut app.table_state);
+}
 
-            let error_msg = service.error_message.as_deref().unwrap_or("");
+fn render_triage_table(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let services = app.triage_services();
+    let title = format!("Triage: {} down or degraded (1 for all hosts)", app.number_format.count(services.len()));
 
-            let service_row = Row::new(vec![
-                Cell::from(format!("  └─ {}", service.service_name)),
-                Cell::from(format!("{}", service.port)),
+    if services.is_empty() {
+        let no_problems = Paragraph::new("No services are down or degraded")
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(no_problems, area);
+        return;
+    }
+
+    // Services drop off the list as they recover
+    app.triage_selected_index = app.triage_selected_index.min(services.len() - 1);
+    let selected_style = Style::default().fg(app.theme.selected_fg).bg(app.theme.selected_bg);
+
+    let rows: Vec<Row> = services
+        .iter()
+        .enumerate()
+        .map(|(index, service)| {
+            let error_msg = service.error_message.as_deref().or(service.detail.as_deref()).unwrap_or("");
+            let acknowledged = app.is_acknowledged(&service.key);
+            Row::new(vec![
+                Cell::from(format!("{}/{}", service.host_name, service.service_name)),
+                Cell::from(port_label(service)),
                 Cell::from(format!("{}", service.protocol)),
-                Cell::from(format!("{}", service.status)),
-                Cell::from(response_time),
+                status_cell(app, service, acknowledged),
+                Cell::from(format!("for {}", format_elapsed(service.status_since))),
                 Cell::from(error_msg),
             ])
-            .style(Style::default()); // No selection styling for service rows
-            rows.push(service_row);
-        }
+            .style(if index == app.triage_selected_index {
+                selected_style
+            } else if acknowledged {
+                Style::default().fg(app.theme.muted).add_modifier(Modifier::DIM)
+            } else {
+                Style::default()
+            })
+        })
+        .collect();
+
+    let table = Table::new(rows, SERVICE_COLUMNS)
+        .header(
+            Row::new(vec!["Host/Service", "Port", "Protocol", "Status", "Duration", "Details"])
+                .style(Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .column_spacing(1);
+
+    let mut state = TableState::default().with_selected(Some(app.triage_selected_index));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// One vantage point's view of a service in the service detail.
+fn vantage_line<'a>(app: &App, name: &str, check: Option<&ServiceCheck>) -> Line<'a> {
+    let theme = &app.theme;
+    let name = Span::styled(format!("  {}: ", name), Style::default().fg(theme.text));
+    let Some(check) = check else {
+        return Line::from(vec![name, Span::styled("not monitored", Style::default().fg(theme.muted))]);
+    };
+    let mut spans = vec![
+        name,
+        Span::styled(app.status_style.label(&check.status), Style::default().fg(theme.status_color(&check.status))),
+    ];
+    if let Some(time) = check.response_time {
+        spans.push(Span::styled(format!(" {}", app.number_format.response_time(time)), Style::default().fg(theme.muted)));
+    }
+    if let Some(error) = &check.error_message {
+        spans.push(Span::styled(format!(" {}", error), Style::default().fg(theme.muted)));
+    }
+    Line::from(spans)
+}
+
+fn status_cell<'a>(app: &App, check: &ServiceCheck, acknowledged: bool) -> Cell<'a> {
+    if check.flapping {
+        return Cell::from(format!("{} FLAPPING", app.status_style.flapping_glyph()))
+            .style(Style::default().fg(app.theme.flapping).add_modifier(Modifier::BOLD));
+    }
+    
+    let mut label = app.status_style.label(&check.status).to_string();
+    if let Some(vantage) = &check.vantage {
+        label = format!("{} @{}", label, vantage);
+    }
+    if check.disabled {
+        Cell::from(format!("{} (off)", label)).style(Style::default().fg(app.theme.muted).add_modifier(Modifier::DIM))
+    } else if check.restored {
+        Cell::from(format!("{} (stale)", label)).style(Style::default().fg(app.theme.muted).add_modifier(Modifier::DIM))
+    } else if acknowledged {
+        Cell::from(format!("{} (ack)", label))
+    } else {
+        Cell::from(label)
+    }
+}
+
+/// Status changes seen this run, newest first, scrolled by `events_scroll`.
+fn render_events(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default().borders(Borders::ALL).title(format!("Status Changes ({})", app.events.len()));
+    if app.events.is_empty() {
+        let message = Paragraph::new(Line::styled("No status changes yet", Style::default().fg(theme.muted)))
+            .block(block)
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(message, area);
+        return;
     }
 
+    let tz = app.config.settings.timezone.parse::<chrono_tz::Tz>().ok();
+    let rows: Vec<Row> = app
+        .events
+        .iter()
+        .rev()
+        .skip(app.events_scroll as usize)
+        .map(|event| {
+            let time = match tz {
+                Some(tz) => event.timestamp.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S").to_string(),
+                None => event.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            };
+            Row::new(vec![
+                Cell::from(time).style(Style::default().fg(theme.muted)),
+                Cell::from(format!("{}/{}", event.host, event.service)),
+                Cell::from(Line::from(vec![
+                    Span::styled(app.status_style.label(&event.old_status), Style::default().fg(theme.status_color(&event.old_status))),
+                    Span::raw(" → "),
+                    Span::styled(app.status_style.label(&event.new_status), Style::default().fg(theme.status_color(&event.new_status))),
+                ])),
+                Cell::from(event.error.clone().unwrap_or_default()),
+            ])
+            .style(Style::default().fg(theme.text))
+        })
+        .collect();
+
     let table = Table::new(
         rows,
-        &[
-            Constraint::Length(25),  // Host/Service - increased
-            Constraint::Length(8),   // Port - kept same
-            Constraint::Length(10),  // Protocol - kept same
-            Constraint::Length(12),  // Status - kept same
-            Constraint::Length(15),  // Response Time - kept same
-            Constraint::Min(20),     // Error - much more space, minimum 20 chars
-        ]
+        [Constraint::Length(19), Constraint::Percentage(25), Constraint::Length(30), Constraint::Min(10)],
     )
     .header(
-        Row::new(vec![
-            "Host/Service",
-            "Port",
-            "Protocol",
-            "Status",
-            "Response Time",
-            "Error",
-        ])
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Row::new(vec!["Time", "Host/Service", "Change", "Error"])
+            .style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
     )
-    .block(Block::default().borders(Borders::ALL).title("Services"))
+    .block(block)
     .column_spacing(1);
-
     f.render_widget(table, area);
 }
 
-fn render_help(f: &mut Frame, area: ratatui::layout::Rect) {
-    let help_text = vec![
-        Line::from(vec![
-            Span::styled("Navigation: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        ]),
-        Line::from(vec![
-            Span::styled("↑/k ", Style::default().fg(Color::Yellow)),
-            Span::styled("- Previous item", Style::default()),
-        ]),
-        Line::from(vec![
-            Span::styled("↓/j ", Style::default().fg(Color::Yellow)),
-            Span::styled("- Next item", Style::default()),
-        ]),
-        Line::from(vec![
-            Span::styled("h ", Style::default().fg(Color::Yellow)),
-            Span::styled("- Toggle help", Style::default()),
-        ]),
-        Line::from(vec![
-            Span::styled("r ", Style::default().fg(Color::Yellow)),
-            Span::styled("- Manual refresh", Style::default()),
-        ]),
-        Line::from(vec![
-            Span::styled("Enter ", Style::default().fg(Color::Yellow)),
-            Span::styled("- View host details", Style::default()),
-        ]),
-        Line::from(vec![
-            Span::styled("b/B ", Style::default().fg(Color::Yellow)),
-            Span::styled("- Back to main view", Style::default()),
-        ]),
-        Line::from(vec![
-            Span::styled("q/ESC ", Style::default().fg(Color::Yellow)),
-            Span::styled("- Quit", Style::default()),
-        ]),
-    ];
+/// Response times of the graphed services over their retained samples, one
+/// line each. The x axis is seconds relative to now.
+fn render_graph(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let now = chrono::Utc::now();
+    let palette = [theme.accent, theme.up, theme.degraded, theme.warning, theme.info, theme.flapping];
+
+    let series: Vec<(String, Vec<(f64, f64)>)> = app
+        .graph_services()
+        .iter()
+        .map(|check| {
+            let points = app
+                .samples
+                .get(&check.key)
+                .map(|samples| {
+                    samples
+                        .iter()
+                        .filter_map(|sample| {
+                            let age = (sample.timestamp - now).num_milliseconds() as f64 / 1000.0;
+                            Some((age, sample.response_time?.as_secs_f64() * 1000.0))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            (format!("{}/{}", check.host_name, check.service_name), points)
+        })
+        .collect();
+
+    let block = Block::default().borders(Borders::ALL).title("Response Times");
+    if series.iter().all(|(_, points)| points.is_empty()) {
+        let message = Paragraph::new(Line::styled("No response times recorded yet", Style::default().fg(theme.muted)))
+            .block(block)
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let points = series.iter().flat_map(|(_, points)| points);
+    let oldest = points.clone().map(|&(x, _)| x).fold(-1.0, f64::min);
+    let slowest = points.map(|&(_, y)| y).fold(1.0, f64::max) * 1.1;
+
+    let datasets = series
+        .iter()
+        .zip(palette.iter().cycle())
+        .map(|((name, points), color)| {
+            Dataset::default()
+                .name(name.clone())
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(points)
+        })
+        .collect();
+
+    let ago = |seconds: f64| format!("{} ago", format_elapsed(now + chrono::Duration::milliseconds((seconds * 1000.0) as i64)));
+    let millis = |ms: f64| app.number_format.response_time(Duration::from_secs_f64(ms / 1000.0));
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.muted))
+                .bounds([oldest, 0.0])
+                .labels(vec![Span::raw(ago(oldest)), Span::raw(ago(oldest / 2.0)), Span::raw("now")]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.muted))
+                .bounds([0.0, slowest])
+                .labels(vec![Span::raw(millis(0.0)), Span::raw(millis(slowest / 2.0)), Span::raw(millis(slowest))]),
+        )
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)));
+    f.render_widget(chart, area);
+}
+
+/// The port column's text; `-` for protocols without a port.
+fn port_label(check: &ServiceCheck) -> String {
+    if check.protocol.uses_port() {
+        check.port.to_string()
+    } else {
+        "-".to_string()
+    }
+}
+
+/// Formats how long ago `since` was as the two largest units, e.g. `2h13m`.
+fn format_elapsed(since: chrono::DateTime<chrono::Utc>) -> String {
+    let seconds = (chrono::Utc::now() - since).num_seconds().max(0);
+    let (days, hours, minutes) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60);
+    if days > 0 {
+        format!("{}d{}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h{}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m{}s", minutes, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+fn sort_header(label: &str, active: bool, arrow: &str) -> String {
+    if active {
+        format!("{} {}", label, arrow)
+    } else {
+        label.to_string()
+    }
+}
+
+/// Lists every key binding by where it applies, generated from the keymap
+/// so it can't drift from what the keys actually do.
+fn render_help(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let mut help_text = Vec::new();
+    for context in KeyContext::ALL {
+        let bindings: Vec<_> = KEY_BINDINGS.iter().filter(|binding| binding.context == context).collect();
+        if bindings.is_empty() {
+            continue;
+        }
+        if !help_text.is_empty() {
+            help_text.push(Line::from(""));
+        }
+        help_text.push(Line::styled(
+            format!("{}:", context.label()),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        ));
+        for binding in bindings {
+            help_text.push(Line::from(vec![
+                Span::styled(format!("{} ", binding.keys), Style::default().fg(theme.key)),
+                Span::styled(format!("- {}", binding.description), Style::default()),
+            ]));
+        }
+    }
+
+    // Help lines are short, so each takes one row
+    let height = area.height.saturating_sub(2) as usize;
+    app.help_scroll = app.help_scroll.min(help_text.len().saturating_sub(height) as u16);
+    let title = if help_text.len() > height { "Help (↑/↓ to scroll)" } else { "Help" };
 
     let help = Paragraph::new(help_text)
-        .block(Block::default().borders(Borders::ALL).title("Help"))
-        .wrap(Wrap { trim: true });
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .scroll((app.help_scroll, 0));
 
     f.render_widget(help, area);
 }
@@ -349,12 +964,52 @@ fn render_status_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         Err(_) => now.format("%H:%M:%S UTC"),
     };
     
-    let status_text = if app.show_help {
+    let status_text = if app.filter_editing {
+        format!("🕐 {} | Filter: {}_ | Press 'Enter' to apply | Press 'Esc' to clear", formatted_time, app.filter)
+    } else if app.note_target.is_some() {
+        format!("🕐 {} | Note: {}_ | Press 'Enter' to save (empty clears) | Press 'Esc' to cancel", formatted_time, app.note_input)
+    } else if app.show_help {
         format!("🕐 {} | Press 'h' to hide help | Press 'q' to quit", formatted_time)
-    } else if app.show_host_detail {
+    } else if app.show_graph {
+        format!("🕐 {} | Response time graph | Press 'g' or 'b' to close", formatted_time)
+    } else if app.show_events {
+        format!("🕐 {} | Status changes | Press ↑/↓ to scroll | Press 'e' or 'b' to close", formatted_time)
+    } else if app.in_detail_view() {
         format!("🕐 {} | Press 'b' to go back | Press 'q' to quit", formatted_time)
+    } else if app.show_triage {
+        format!("🕐 {} | Triage view | Press '1' for all hosts | Press 'Enter' for details", formatted_time)
+    } else {
+        format!("🕐 {} | Press 'h' for help | Press 'q' to quit | Press 'r' to refresh | Press 'Enter' for details", formatted_time)
+    };
+
+    let editing = app.filter_editing || app.note_target.is_some();
+    let status_text = if app.paused && !editing {
+        format!("⏸ PAUSED (p to resume) | {}", status_text)
     } else {
-        format!("🕐 {} | Press 'h' for help | Press 'q' to quit | Press 'r' to refresh | Press 'Enter' for host details", formatted_time)
+        status_text
+    };
+
+    let status_text = if app.is_filter_active() && !editing {
+        format!("{} | Filter: \"{}\" (Esc to clear)", status_text, app.filter)
+    } else {
+        status_text
+    };
+
+    let status_text = match &app.tag_filter {
+        Some(tag) if !editing => format!("{} | Tag: {} (t to change)", status_text, tag),
+        _ => status_text,
+    };
+
+    let status_text = match app.stale_data_age() {
+        Some(age) if !editing => {
+            format!("⚠ No new results for {}s, checks may have stopped | {}", age.as_secs(), status_text)
+        }
+        _ => status_text,
+    };
+
+    let status_text = match app.status_message() {
+        Some(message) if !editing => format!("{} | {}", message, status_text),
+        _ => status_text,
     };
 
     let status = Paragraph::new(status_text)
@@ -364,24 +1019,201 @@ fn render_status_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     f.render_widget(status, area);
 }
 
-fn render_host_detail(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+fn render_service_detail(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let Some(check) = app.get_detail_service() else {
+        let error_widget = Paragraph::new("Service not found")
+            .block(Block::default().borders(Borders::ALL).title("Error"))
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(error_widget, area);
+        return;
+    };
+    let theme = &app.theme;
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD));
+
+    let target = app
+        .find_service_config(check)
+        .and_then(|(host, service)| crate::monitor::service_url(&host.address, service))
+        .unwrap_or_else(|| match check.protocol {
+            Protocol::Unix => check.address.clone(),
+            _ => crate::monitor::socket_address(&check.address, check.port),
+        });
+
+    let last_check = match app.config.settings.timezone.parse::<chrono_tz::Tz>() {
+        Ok(tz) => check.last_check.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S %Z").to_string(),
+        Err(_) => check.last_check.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+    };
+
+    let mut lines = vec![
+        Line::from(vec![label("Service: "), Span::styled(&check.service_name, Style::default().fg(theme.text))]),
+        Line::from(vec![label("Host: "), Span::styled(&check.host_name, Style::default().fg(theme.text))]),
+        Line::from(vec![label("Target: "), Span::styled(target, Style::default().fg(theme.text))]),
+        Line::from(vec![label("Protocol: "), Span::styled(check.protocol.to_string(), Style::default().fg(theme.text))]),
+        Line::from(vec![
+            label("Status: "),
+            Span::styled(app.status_style.label(&check.status), Style::default().fg(theme.status_color(&check.status))),
+            Span::styled(format!(" for {}", format_elapsed(check.status_since)), Style::default().fg(theme.muted)),
+        ]),
+        Line::from(vec![label("Last check: "), Span::styled(last_check, Style::default().fg(theme.text))]),
+        Line::from(vec![
+            label("Response time: "),
+            Span::styled(
+                check.response_time.map(|time| app.number_format.response_time(time)).unwrap_or_else(|| "N/A".to_string()),
+                Style::default().fg(theme.text),
+            ),
+        ]),
+    ];
+    if check.raw_status != check.status {
+        lines.push(Line::from(vec![
+            label("Last result: "),
+            Span::styled(app.status_style.label(&check.raw_status), Style::default().fg(theme.status_color(&check.raw_status))),
+        ]));
+    }
+    if let Some(http_version) = &check.http_version {
+        lines.push(Line::from(vec![
+            label("HTTP version: "),
+            Span::styled(http_version.clone(), Style::default().fg(theme.text)),
+        ]));
+    }
+    for (name, value) in &check.headers {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}: ", name), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(value.clone(), Style::default().fg(theme.text)),
+        ]));
+    }
+    // The local result, then each peer's, when results are rolled up from peers
+    if !app.peer_results.is_empty() {
+        lines.push(Line::from(label("Vantages:")));
+        lines.push(vantage_line(app, "local", app.local_statuses.get(&check.key)));
+        for (name, peer_check) in crate::peers::results_for(&app.peer_results, check) {
+            match app.peer_results.get(name) {
+                Some(Err(e)) => lines.push(Line::from(vec![
+                    Span::styled(format!("  {}: ", name), Style::default().fg(theme.text)),
+                    Span::styled(format!("unreachable ({})", e), Style::default().fg(theme.muted)),
+                ])),
+                _ => lines.push(vantage_line(app, name, peer_check)),
+            }
+        }
+    }
+    let mut messages = Vec::new();
+    if let Some(note) = app.note(&check.key) {
+        messages.push(Line::from(label("Note:")));
+        messages.extend(note.lines().map(|line| Line::styled(line.to_string(), Style::default().fg(theme.warning))));
+    }
+    if let Some(error) = &check.error_message {
+        messages.push(Line::from(label("Error:")));
+        messages.extend(error.lines().map(|line| Line::styled(line.to_string(), Style::default().fg(theme.down))));
+    }
+    if let Some(detail) = &check.detail {
+        messages.push(Line::from(label("Detail:")));
+        messages.extend(detail.lines().map(|line| Line::styled(line.to_string(), Style::default().fg(theme.text))));
+    }
+    if messages.is_empty() {
+        messages.push(Line::styled("No errors", Style::default().fg(theme.muted)));
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(lines.len() as u16 + 2), // Service info
+            Constraint::Min(3),     // Error and detail messages
+            Constraint::Length(4),  // Status timeline
+        ].as_ref())
+        .split(area);
+
+    let info = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Service Information"))
+        .wrap(Wrap { trim: true });
+    f.render_widget(info, chunks[0]);
+
+    // One block per recent check, newest on the right
+    let width = chunks[2].width.saturating_sub(2) as usize;
+    let samples = app.samples.get(&check.key);
+    let timeline: Vec<Span> = samples
+        .map(|samples| {
+            samples
+                .iter()
+                .skip(samples.len().saturating_sub(width))
+                .map(|sample| Span::styled("█", Style::default().fg(theme.status_color(&sample.status))))
+                .collect()
+        })
+        .unwrap_or_default();
+    let count = timeline.len();
+
+    let timeline = Paragraph::new(vec![
+        Line::from(timeline),
+        Line::from(Span::styled(format!("last {} checks, oldest first", count), Style::default().fg(theme.muted))),
+    ])
+    .block(Block::default().borders(Borders::ALL).title("Recent Status"));
+    f.render_widget(timeline, chunks[2]);
+
+    let scroll = app.detail_scroll;
+    app.detail_scroll = render_scrollable_messages(f, messages, scroll, chunks[1]);
+}
+
+/// Draws wrapped messages that can be scrolled with PageUp/PageDown, returning
+/// the scroll offset clamped to the content.
+fn render_scrollable_messages(f: &mut Frame, lines: Vec<Line<'static>>, scroll: u16, area: Rect) -> u16 {
+    let width = area.width.saturating_sub(2).max(1) as usize;
+    let height = area.height.saturating_sub(2) as usize;
+    let wrapped: usize = lines
+        .iter()
+        .map(|line| line.width().div_ceil(width).max(1))
+        .sum();
+    let scroll = scroll.min(wrapped.saturating_sub(height) as u16);
+
+    let title = if wrapped > height {
+        "Messages (PgUp/PgDn to scroll)"
+    } else {
+        "Messages"
+    };
+    let messages = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    f.render_widget(messages, area);
+
+    scroll
+}
+
+fn render_host_detail(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
     if let Some(host) = app.get_selected_host() {
         let host_services = app.get_host_services_status(&host.name);
         
+        // Full error text for every failing service, since the table truncates it
+        let mut messages = Vec::new();
+        for service in host_services.iter().filter(|s| s.error_message.is_some()) {
+            messages.push(Line::styled(
+                format!("{}:", service.service_name),
+                Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD),
+            ));
+            for line in service.error_message.as_deref().unwrap_or("").lines() {
+                messages.push(Line::styled(format!("  {}", line), Style::default().fg(app.theme.down)));
+            }
+        }
+        
         // Create layout for host detail
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(8),  // Host info
                 Constraint::Min(0),     // Services table
+                Constraint::Length(host_services.len().min(6) as u16 + 3),  // Response time percentiles
+                Constraint::Length(if messages.is_empty() { 0 } else { 8 }),  // Errors
             ].as_ref())
             .split(area);
 
         // Render host information
-        render_host_info(f, host, chunks[0]);
+        render_host_info(f, &app.theme, host, app.config.host_refresh_interval(host), chunks[0]);
         
         // Render services table
         render_host_services_table(f, app, &host_services, chunks[1]);
+        
+        render_response_percentiles(f, app, &host_services, chunks[2]);
+        
+        if !messages.is_empty() {
+            let scroll = app.detail_scroll;
+            app.detail_scroll = render_scrollable_messages(f, messages, scroll, chunks[3]);
+        }
     } else {
         let error_text = "Host not found";
         let error_widget = Paragraph::new(error_text)
@@ -391,35 +1223,81 @@ fn render_host_detail(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     }
 }
 
-fn render_host_info(f: &mut Frame, host: &crate::config::Host, area: ratatui::layout::Rect) {
+fn render_response_percentiles(f: &mut Frame, app: &App, services: &[crate::monitor::ServiceCheck], area: ratatui::layout::Rect) {
+    let rows: Vec<Row> = services
+        .iter()
+        .map(|service| {
+            let cells = match app.response_percentiles(&service.key) {
+                Some(p) => [
+                    p.samples.to_string(),
+                    app.number_format.response_time(p.p50),
+                    app.number_format.response_time(p.p95),
+                    app.number_format.response_time(p.p99),
+                ],
+                None => ["-".to_string(), "n/a".to_string(), "n/a".to_string(), "n/a".to_string()],
+            };
+            let mut row = vec![Cell::from(service.service_name.clone())];
+            row.extend(cells.into_iter().map(Cell::from));
+            Row::new(row).style(Style::default().fg(app.theme.text))
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        &[
+            Constraint::Length(30),  // Service Name
+            Constraint::Length(8),   // Samples
+            Constraint::Length(10),  // p50
+            Constraint::Length(10),  // p95
+            Constraint::Length(10),  // p99
+        ]
+    )
+    .header(
+        Row::new(vec!["Service Name", "Samples", "p50", "p95", "p99"])
+            .style(Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title("Response Times"))
+    .column_spacing(1);
+
+    f.render_widget(table, area);
+}
+
+fn render_host_info(f: &mut Frame, theme: &Theme, host: &crate::config::Host, refresh_interval: u64, area: ratatui::layout::Rect) {
     let host_text = vec![
         Line::from(vec![
-            Span::styled("Host: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-            Span::styled(&host.name, Style::default().fg(Color::White)),
+            Span::styled("Host: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&host.name, Style::default().fg(theme.text)),
         ]),
         Line::from(vec![
-            Span::styled("Address: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-            Span::styled(&host.address, Style::default().fg(Color::White)),
+            Span::styled("Address: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(&host.address, Style::default().fg(theme.text)),
         ]),
         Line::from(vec![
-            Span::styled("Description: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled("Description: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
             Span::styled(
                 host.description.as_deref().unwrap_or("No description"),
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             ),
         ]),
         Line::from(vec![
-            Span::styled("Services: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled("Services: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
             Span::styled(
                 format!("{}", host.services.len()),
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             ),
         ]),
         Line::from(vec![
-            Span::styled("Timeout: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled("Timeout: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
             Span::styled(
                 format!("{}s", host.timeout),
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Refresh: ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(
+                format!("{}s", refresh_interval),
+                Style::default().fg(theme.text)
             ),
         ]),
     ];
@@ -431,7 +1309,7 @@ fn render_host_info(f: &mut Frame, host: &crate::config::Host, area: ratatui::la
     f.render_widget(host_info, area);
 }
 
-fn render_host_services_table(f: &mut Frame, _app: &App, services: &[crate::monitor::ServiceCheck], area: ratatui::layout::Rect) {
+fn render_host_services_table(f: &mut Frame, app: &mut App, services: &[crate::monitor::ServiceCheck], area: ratatui::layout::Rect) {
     if services.is_empty() {
         let no_data = Paragraph::new("No services available for this host...")
             .block(Block::default().borders(Borders::ALL).title("Services"))
@@ -440,32 +1318,30 @@ fn render_host_services_table(f: &mut Frame, _app: &App, services: &[crate::moni
         return;
     }
 
+    // Keep the selection within the table
+    app.detail_selected_index = app.detail_selected_index.min(services.len() - 1);
+    let selected_style = Style::default().fg(app.theme.selected_fg).bg(app.theme.selected_bg);
+
     let rows: Vec<Row> = services
         .iter()
-        .map(|status| {
-            let _status_color = match status.status {
-                crate::monitor::ServiceStatus::Up => Color::Green,
-                crate::monitor::ServiceStatus::Down => Color::Red,
-                crate::monitor::ServiceStatus::Unknown => Color::Yellow,
-            };
+        .enumerate()
+        .map(|(index, status)| {
+            let _status_color = app.theme.status_color(&status.status);
 
-            let response_time = if status.response_time.as_millis() > 0 {
-                format!("{}ms", status.response_time.as_millis())
-            } else {
-                "N/A".to_string()
-            };
+            let response_time = status.response_time.map(|time| app.number_format.response_time(time)).unwrap_or_else(|| "N/A".to_string());
 
-            let error_msg = status.error_message.as_deref().unwrap_or("");
+            let error_msg = status.error_message.as_deref().or(status.detail.as_deref()).unwrap_or("");
 
             Row::new(vec![
-                Cell::from(format!("{}", status.service_name)),
-                Cell::from(format!("{}", status.port)),
+                Cell::from(status.service_name.to_string()),
+                Cell::from(port_label(status)),
                 Cell::from(format!("{}", status.protocol)),
-                Cell::from(format!("{}", status.status)),
+                status_cell(app, status, app.is_acknowledged(&status.key)),
+                Cell::from(format!("for {}", format_elapsed(status.status_since))),
                 Cell::from(response_time),
                 Cell::from(error_msg),
             ])
-            .style(Style::default())
+            .style(if index == app.detail_selected_index { selected_style } else { Style::default() })
         })
         .collect();
 
@@ -475,9 +1351,10 @@ fn render_host_services_table(f: &mut Frame, _app: &App, services: &[crate::moni
             Constraint::Length(30),  // Service Name - kept same
             Constraint::Length(8),   // Port - kept same
             Constraint::Length(10),  // Protocol - kept same
-            Constraint::Length(12),  // Status - kept same
+            Constraint::Length(14),  // Status - fits ascii labels
+            Constraint::Length(10),  // Time in current status
             Constraint::Length(15),  // Response Time - kept same
-            Constraint::Min(25),     // Error - much more space, minimum 25 chars
+            Constraint::Min(25),     // Details - much more space, minimum 25 chars
         ]
     )
     .header(
@@ -486,13 +1363,16 @@ fn render_host_services_table(f: &mut Frame, _app: &App, services: &[crate::moni
             "Port",
             "Protocol",
             "Status",
+            "Duration",
             "Response Time",
-            "Error",
+            "Details",
         ])
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        .style(Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD)),
     )
     .block(Block::default().borders(Borders::ALL).title("Host Services"))
     .column_spacing(1);
 
-    f.render_widget(table, area);
+    // Scrolls the table to keep the selected row visible
+    let mut state = TableState::default().with_selected(Some(app.detail_selected_index));
+    f.render_stateful_widget(table, area, &mut state);
 } 
\ No newline at end of file
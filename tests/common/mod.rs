@@ -0,0 +1,34 @@
+// Each test crate uses only some of these
+#![allow(dead_code)]
+
+use daystrom_tui::config::{Config, ConfigFormat};
+use daystrom_tui::monitor::ServiceCheck;
+use daystrom_tui::MonitorEngine;
+
+/// Parses a YAML config, failing the test if it doesn't load.
+pub fn load(yaml: &str) -> Config {
+    Config::parse(yaml, ConfigFormat::Yaml).expect("test config should parse")
+}
+
+/// An engine for one host at `address` with one service, both given as YAML
+/// flow mappings, e.g. `{ name: "web", port: 80, protocol: "http" }`.
+pub fn single_service(settings: &str, address: &str, service: &str) -> MonitorEngine {
+    MonitorEngine::new(load(&format!(
+        r#"
+settings: {}
+hosts:
+  - name: "local"
+    address: "{}"
+    services:
+      - {}
+"#,
+        settings, address, service
+    )))
+}
+
+/// Runs one check cycle and returns the result of the engine's only service.
+pub async fn check_one(engine: &MonitorEngine) -> ServiceCheck {
+    let mut statuses = engine.check_once().await;
+    assert_eq!(statuses.len(), 1);
+    statuses.remove(0)
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use daystrom_tui::monitor::{socket_address, ServiceStatus};
+
+#[test]
+fn ipv6_literals_are_bracketed() {
+    assert_eq!(socket_address("::1", 443), "[::1]:443");
+    assert_eq!(socket_address("2001:db8::10", 80), "[2001:db8::10]:80");
+    assert_eq!(socket_address("127.0.0.1", 22), "127.0.0.1:22");
+    assert_eq!(socket_address("example.com", 8080), "example.com:8080");
+}
+
+#[tokio::test]
+async fn tcp_check_reaches_ipv6_loopback() {
+    // Some CI hosts have IPv6 disabled entirely
+    let Ok(listener) = tokio::net::TcpListener::bind("[::1]:0").await else {
+        eprintln!("IPv6 loopback unavailable, skipping");
+        return;
+    };
+    let port = listener.local_addr().unwrap().port();
+
+    let engine = common::single_service(
+        "{}",
+        "::1",
+        &format!(r#"{{ name: "listener", port: {}, protocol: "tcp", timeout: 2 }}"#, port),
+    );
+
+    let check = common::check_one(&engine).await;
+    assert_eq!(check.status, ServiceStatus::Up, "error: {:?}", check.error_message);
+}
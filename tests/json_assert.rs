@@ -0,0 +1,58 @@
+use daystrom_tui::config::{Config, ConfigFormat};
+use daystrom_tui::json_assert::JsonAssertion;
+
+fn evaluate(expression: &str, body: &str) -> Result<(), String> {
+    JsonAssertion::parse(expression).expect("assertion should parse").evaluate(body)
+}
+
+#[test]
+fn equality_holds_or_reports_actual_value() {
+    assert_eq!(evaluate(r#"$.status == "ok""#, r#"{"status": "ok"}"#), Ok(()));
+    assert_eq!(
+        evaluate(r#"$.status == "ok""#, r#"{"status": "failing"}"#),
+        Err(r#"$.status is "failing", expected "ok""#.to_string())
+    );
+    assert_eq!(evaluate("$.count == 3", r#"{"count": 3}"#), Ok(()));
+    assert!(evaluate("$.count == 3", r#"{"count": "3"}"#).is_err());
+}
+
+#[test]
+fn bare_path_only_requires_the_field() {
+    assert_eq!(evaluate("$.version", r#"{"version": null}"#), Ok(()));
+    assert_eq!(evaluate("$.version", "{}"), Err("$.version not found in response".to_string()));
+}
+
+#[test]
+fn indexes_and_quoted_keys_are_followed() {
+    let body = r#"{"checks": [{"db": true, "disk space": false}]}"#;
+    assert_eq!(evaluate(r#"$.checks[0]["db"] == true"#, body), Ok(()));
+    assert_eq!(evaluate(r#"$.checks[0]["disk space"] == false"#, body), Ok(()));
+    assert!(evaluate("$.checks[1].db", body).is_err());
+}
+
+#[test]
+fn non_json_body_fails() {
+    let error = evaluate("$.status", "<html>").unwrap_err();
+    assert!(error.starts_with("Response is not valid JSON"), "{}", error);
+}
+
+#[test]
+fn malformed_expressions_are_rejected() {
+    for expression in ["status == \"ok\"", "$.checks[0", "$.checks[x]", "$..status", "$.status == ok"] {
+        assert!(JsonAssertion::parse(expression).is_err(), "{} should not parse", expression);
+    }
+}
+
+#[test]
+fn malformed_expression_fails_config_load() {
+    let yaml = r#"
+settings: {}
+hosts:
+  - name: "api"
+    address: "127.0.0.1"
+    services:
+      - { name: "health", port: 80, protocol: "http", json_assert: "status == 1" }
+"#;
+    let error = Config::parse(yaml, ConfigFormat::Yaml).unwrap_err();
+    assert!(format!("{:#}", error).contains("invalid json_assert 'status == 1'"), "{:#}", error);
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::check_one;
+use daystrom_tui::monitor::ServiceStatus;
+use std::time::Duration;
+
+#[tokio::test]
+async fn pooled_connection_is_reopened_once_the_server_closes_it() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let engine = common::single_service(
+        "{}",
+        "127.0.0.1",
+        &format!(r#"{{ name: "pooled", port: {}, protocol: "tcp", timeout: 1, keep_alive: true }}"#, port),
+    );
+
+    let opened = check_one(&engine).await;
+    assert_eq!(opened.status, ServiceStatus::Up, "error: {:?}", opened.error_message);
+    assert_eq!(opened.detail.as_deref(), Some("Opened new connection"));
+    assert!(opened.response_time.is_some());
+
+    let reused = check_one(&engine).await;
+    assert_eq!(reused.detail.as_deref(), Some("Reused open connection"));
+    assert_eq!(reused.response_time, None);
+
+    // Closing the server side makes the next check reconnect
+    let (first, _) = listener.accept().await.unwrap();
+    drop(first);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let reopened = check_one(&engine).await;
+    assert_eq!(reopened.status, ServiceStatus::Up, "error: {:?}", reopened.error_message);
+    assert_eq!(reopened.detail.as_deref(), Some("Opened new connection"));
+
+    // With nothing listening any more, that reconnect fails
+    let (second, _) = listener.accept().await.unwrap();
+    drop(second);
+    drop(listener);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(check_one(&engine).await.status, ServiceStatus::Down);
+}
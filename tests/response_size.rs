@@ -0,0 +1,35 @@
+mod common;
+
+use daystrom_tui::monitor::ServiceStatus;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn chunked_body_over_the_limit_is_degraded() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0u8; 1024];
+        let _ = socket.read(&mut request).await;
+        // No Content-Length, so only streaming the body notices its size
+        let chunk = "x".repeat(600);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+            chunk.len(),
+            chunk,
+            chunk.len(),
+            chunk
+        );
+        let _ = socket.write_all(response.as_bytes()).await;
+    });
+
+    let engine = common::single_service(
+        "{ max_response_bytes: 1000 }",
+        "127.0.0.1",
+        &format!(r#"{{ name: "web", port: {}, protocol: "http", timeout: 2 }}"#, port),
+    );
+
+    let check = common::check_one(&engine).await;
+    assert_eq!(check.status, ServiceStatus::Degraded, "error: {:?}", check.error_message);
+    assert_eq!(check.error_message.as_deref(), Some("Response too large (over 1000 bytes)"));
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::load;
+use daystrom_tui::{App, MonitorEngine};
+
+#[tokio::test]
+async fn services_sharing_name_and_port_get_distinct_keys() {
+    let config = load(
+        r#"
+settings: {}
+hosts:
+  - name: "local"
+    address: "127.0.0.1"
+    services:
+      - { name: "web", port: 1, protocol: "tcp", timeout: 1 }
+      - { name: "web", port: 1, protocol: "tcp", timeout: 1 }
+"#,
+    );
+    let engine = MonitorEngine::new(config.clone());
+    engine.check_all_services().await;
+
+    let statuses = engine.get_statuses().await;
+    assert_eq!(statuses.len(), 2);
+
+    let mut app = App::new(config, engine);
+    app.update_statuses().await;
+
+    assert_eq!(app.get_host_services_status("local").len(), 2);
+
+    let grouped = app.get_grouped_status_list();
+    assert_eq!(grouped.len(), 1);
+    assert_eq!(grouped[0].1.len(), 2);
+}
+
+#[tokio::test]
+async fn services_sharing_name_with_different_protocols_are_kept() {
+    let config = load(
+        r#"
+settings: {}
+hosts:
+  - name: "local"
+    address: "127.0.0.1"
+    services:
+      - { name: "web", port: 1, protocol: "tcp", timeout: 1 }
+      - { name: "web", port: 1, protocol: "udp", timeout: 1 }
+"#,
+    );
+    let engine = MonitorEngine::new(config);
+    engine.check_all_services().await;
+
+    let statuses = engine.get_statuses().await;
+    let mut keys: Vec<_> = statuses.values().map(|check| check.key.clone()).collect();
+    keys.sort();
+    keys.dedup();
+    assert_eq!(keys.len(), 2);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::load;
+use daystrom_tui::config::{Config, ConfigFormat};
+
+#[test]
+fn host_services_override_template_fields() {
+    let config = load(
+        r#"
+settings: {}
+service_templates:
+  web:
+    - name: "HTTP"
+      protocol: "http"
+      path: "/health"
+      timeout: 3
+      headers: { Accept: "application/json", X-Probe: "template" }
+    - { name: "SSH", port: 22, protocol: "tcp" }
+hosts:
+  - name: "web-1"
+    address: "10.0.0.11"
+    use_template: web
+    services:
+      - name: "HTTP"
+        port: 8080
+        headers: { X-Probe: "host" }
+      - { name: "Metrics", port: 9100, protocol: "http" }
+"#,
+    );
+
+    let services = &config.hosts[0].services;
+    let names: Vec<&str> = services.iter().map(|service| service.name.as_str()).collect();
+    assert_eq!(names, ["HTTP", "SSH", "Metrics"]);
+
+    let http = &services[0];
+    assert_eq!(http.port, 8080);
+    assert_eq!(http.path.as_deref(), Some("/health"));
+    assert_eq!(http.timeout, 3);
+    assert_eq!(http.headers.get("X-Probe").map(String::as_str), Some("host"));
+    assert_eq!(http.headers.get("Accept").map(String::as_str), Some("application/json"));
+    assert_eq!(services[1].port, 22);
+}
+
+#[test]
+fn later_templates_override_earlier_ones() {
+    let config = load(
+        r#"
+settings: {}
+service_templates:
+  base:
+    - { name: "HTTP", port: 80, protocol: "http", path: "/" }
+  tuned:
+    - { name: "HTTP", path: "/ready" }
+hosts:
+  - name: "web-1"
+    address: "10.0.0.11"
+    use_template: [base, tuned]
+"#,
+    );
+
+    let services = &config.hosts[0].services;
+    assert_eq!(services.len(), 1);
+    assert_eq!(services[0].port, 80);
+    assert_eq!(services[0].path.as_deref(), Some("/ready"));
+}
+
+#[test]
+fn unknown_template_is_an_error() {
+    let error = Config::parse(
+        r#"
+settings: {}
+service_templates:
+  web:
+    - { name: "HTTP", protocol: "http" }
+hosts:
+  - name: "web-1"
+    address: "10.0.0.11"
+    use_template: wbe
+"#,
+        ConfigFormat::Yaml,
+    )
+    .unwrap_err();
+
+    assert!(
+        format!("{:#}", error).contains("host 'web-1' uses unknown service template 'wbe'"),
+        "{:#}",
+        error
+    );
+}